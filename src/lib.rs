@@ -1,6 +1,8 @@
 pub mod puzzles;
 pub mod shared;
 
+use std::path::PathBuf;
+
 use clap::{Parser, Subcommand};
 
 pub const DAYS: [fn(); 25] = [
@@ -38,7 +40,19 @@ pub struct Cli {
     pub run: Option<Commands>,
     /// IF a solution has debug output, print it
     #[clap(long, env)]
-    debug: bool, // --debug or DEBUG env var
+    pub debug: bool, // --debug or DEBUG env var
+    /// Animate simulations that support it
+    #[clap(long, env)]
+    pub visualize: bool,
+    /// Frames per second while animating
+    #[clap(long, env, default_value_t = 10)]
+    pub fps: u32,
+    /// Wait for Enter between animation frames
+    #[clap(long, env)]
+    pub step: bool,
+    /// Write images of grids and simulation frames into this directory
+    #[clap(long, env)]
+    pub export: Option<PathBuf>,
 }
 
 #[derive(Subcommand, Clone, Debug)]
@@ -1,9 +1,23 @@
-use aoc24::{Cli, Commands, DAYS};
+use aoc24::{
+    shared::{
+        image,
+        render::{self, Settings},
+        util,
+    },
+    Cli, Commands, DAYS,
+};
 use clap::Parser;
 
 fn main() {
     env_logger::init();
     let cli = Cli::parse();
+    render::configure(Settings {
+        visualize: cli.visualize,
+        fps: cli.fps,
+        step: cli.step,
+    });
+    image::configure(cli.export);
+    util::set_debug(cli.debug);
     if let Some(subcommand) = cli.run {
         match subcommand {
             Commands::All => {
@@ -1,11 +1,9 @@
-use std::ops::Mul;
-
-use crate::shared::util::read_lines;
+use crate::shared::{counter::Counter, util::Input};
 
 fn parse() -> (Vec<usize>, Vec<usize>) {
     let mut one = vec![];
     let mut two = vec![];
-    for l in read_lines("inputs/day01.txt") {
+    for l in Input::read("inputs/day01.txt").lines() {
         let mut split = l.split_whitespace();
         one.push(split.next().unwrap().parse::<usize>().unwrap());
         two.push(split.next().unwrap().parse::<usize>().unwrap());
@@ -21,10 +19,8 @@ fn part1(a: &[usize], b: &[usize]) {
 }
 
 fn part2(a: &[usize], b: &[usize]) {
-    let result: usize = a
-        .iter()
-        .map(|x| x.mul(b.iter().filter(|i| *i == x).count()))
-        .sum();
+    let counts = b.iter().collect::<Counter<_>>();
+    let result: usize = a.iter().map(|x| x * counts.get(&x)).sum();
     println!("Part2: {result}");
 }
 
@@ -1,25 +1,12 @@
 use std::cmp::Ordering;
 
-use nom::{
-    character::complete::{space1, u32},
-    multi::separated_list1,
-    IResult,
+use crate::shared::{
+    parse::{number_list, parse_lines},
+    util::Input,
 };
 
-use crate::shared::util::read_lines;
-
-fn parse() -> Vec<Vec<u32>> {
-    read_lines("inputs/day02.txt")
-        .iter()
-        .map(|s| match parse_line(s) {
-            Ok((_, digits)) => digits,
-            Err(e) => panic!("Error reading input: {e:?}"),
-        })
-        .collect::<Vec<_>>()
-}
-
-fn parse_line(s: &str) -> IResult<&str, Vec<u32>> {
-    separated_list1(space1, u32)(s)
+fn parse(input: &str) -> Vec<Vec<u32>> {
+    parse_lines(input, number_list(" ")).unwrap_or_else(|e| panic!("Error reading input: {e}"))
 }
 
 fn is_safe(report: &[u32]) -> bool {
@@ -38,7 +25,8 @@ fn is_safe(report: &[u32]) -> bool {
 }
 
 pub fn solve() {
-    let input = parse();
+    let input = Input::read("inputs/day02.txt");
+    let input = parse(&input);
     part1(&input);
     part2(&input);
 }
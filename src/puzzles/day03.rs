@@ -1,10 +1,10 @@
 use regex::Regex;
 
-use crate::shared::util::read_lines;
+use crate::shared::util::Input;
 
-fn parse_mul(input: &[String]) -> (u32, u32) {
+fn parse_mul(input: &str) -> (u32, u32) {
     // Lazy, but rejoin after having removed `\n`ewlines.
-    let joined = input.concat();
+    let joined = input.lines().collect::<String>();
     let re = Regex::new(r"mul\((\d+,\d+)\)|(do\(\))|(don't\(\))").expect("invalid regex");
 
     // part1
@@ -29,7 +29,7 @@ fn parse_mul(input: &[String]) -> (u32, u32) {
 }
 
 pub fn solve() {
-    let input = read_lines("inputs/day03.txt");
+    let input = Input::read("inputs/day03.txt");
     let (part1_res, part2_res) = parse_mul(&input);
     println!("Part 1: {}", part1_res);
     println!("Part 2: {}", part2_res);
@@ -41,10 +41,9 @@ mod test {
 
     #[test]
     fn test_solution() {
-        let test_input = vec![
-            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))".to_string(),
-        ];
-        let (p1, p2) = parse_mul(&test_input);
+        let test_input =
+            "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
+        let (p1, p2) = parse_mul(test_input);
         eprintln!("P1: {p1}, P2: {p2}");
         assert_eq!(161, p1);
         assert_eq!(48, p2);
@@ -2,18 +2,12 @@ use grid::Grid;
 
 use crate::shared::{
     grid2d::{iter_diag_nesw, iter_diag_nwse, Point},
-    util::read_lines,
+    util::Input,
 };
 
-fn parse_grid(input: &[String]) -> Grid<u8> {
-    let cols = input.first().unwrap().len();
-    Grid::from_vec(
-        input
-            .iter()
-            .flat_map(|row| row.chars().map(|c| c as u8).collect::<Vec<u8>>())
-            .collect(),
-        cols,
-    )
+fn parse_grid(input: &Input) -> Grid<u8> {
+    let cols = input.lines().next().unwrap().len();
+    Grid::from_vec(input.lines().flat_map(str::bytes).collect(), cols)
 }
 
 fn part1(grid: &Grid<u8>) -> usize {
@@ -66,7 +60,7 @@ fn part2(grid: &Grid<u8>) -> usize {
 }
 
 pub fn solve() {
-    let input = read_lines("inputs/day04.txt");
+    let input = Input::read("inputs/day04.txt");
     let grid = parse_grid(&input);
     println!("Part 1: {}", part1(&grid));
     println!("Part 2: {}", part2(&grid));
@@ -90,7 +84,7 @@ MXMXAXMASX";
 
     #[test]
     fn test_part_1() {
-        let input = TEST_INPUT.lines().map(str::to_string).collect::<Vec<_>>();
+        let input = Input::from(TEST_INPUT);
         let grid = parse_grid(&input);
         let result = part1(&grid);
         assert_eq!(18, result)
@@ -98,7 +92,7 @@ MXMXAXMASX";
 
     #[test]
     fn test_part_2() {
-        let input = TEST_INPUT.lines().map(str::to_string).collect::<Vec<_>>();
+        let input = Input::from(TEST_INPUT);
         let grid = parse_grid(&input);
         let result = part2(&grid);
         assert_eq!(9, result)
@@ -1,89 +1,65 @@
-use std::{cmp::Ordering, fs::read_to_string};
+use nom::{character::complete::char, sequence::separated_pair};
 
-use simple_grid::Grid;
+use crate::shared::{
+    ordering::PartialOrder,
+    parse::{integer, number_list, sections, Section},
+    util::Input,
+};
 
 type Idx = (usize, usize);
-type Matrix = Grid<Ordering>;
 type Page = Vec<usize>;
 
 fn parse_input(input: &str) -> (Vec<Idx>, Vec<Page>) {
-    let split: Vec<&str> = input.split("\n\n").collect();
-    let (pair_str, page_str) = (split[0], split[1]);
-    let pairs = parse_pairs(pair_str);
-    let pages = parse_pages(page_str);
+    let [pair_section, page_section] = sections(input)[..] else {
+        panic!("expected ordering rules and updates separated by a blank line");
+    };
+    let pairs = parse_pairs(pair_section);
+    let pages = parse_pages(page_section);
     (pairs, pages)
 }
 
-fn parse_pairs(input: &str) -> Vec<Idx> {
-    input
-        .lines()
-        .map(|l| {
-            let (a, b) = l.split_once('|').unwrap();
-            (a.parse().unwrap(), b.parse().unwrap())
-        })
-        .collect()
+fn parse_pairs(section: Section) -> Vec<Idx> {
+    section
+        .parse_lines(separated_pair(integer, char('|'), integer))
+        .unwrap_or_else(|e| panic!("{e}"))
 }
 
-fn parse_pages(input: &str) -> Vec<Page> {
-    input
-        .lines()
-        .map(|l| -> Page {
-            l.split(",")
-                .map(|d| d.parse::<usize>().expect("invalid digit"))
-                .collect()
-        })
-        .collect()
-}
-
-fn create_matrix(pairs: &[Idx]) -> Matrix {
-    let max = *pairs
-        .iter()
-        .flat_map(|(a, b)| [a, b])
-        .max()
-        .expect("iterator is non-empty")
-        + 1;
-    let mut matrix = Grid::new(max, max, vec![Ordering::Equal; max * max]);
-    for (a, b) in pairs {
-        matrix.replace_cell((*a, *b), Ordering::Less);
-        matrix.replace_cell((*b, *a), Ordering::Greater);
-    }
-    matrix
+fn parse_pages(section: Section) -> Vec<Page> {
+    section
+        .parse_lines(number_list(","))
+        .unwrap_or_else(|e| panic!("{e}"))
 }
 
-fn valid_pages(pages: &[Page], matrix: &Matrix) -> usize {
+fn valid_pages(pages: &[Page], order: &PartialOrder<usize>) -> usize {
     pages
         .iter()
-        .filter_map(|p| {
-            if check_order(p, matrix) {
-                Some(p[p.len() / 2])
-            } else {
-                None
-            }
-        })
+        .filter(|p| order.respects(p))
+        .map(|p| p[p.len() / 2])
         .sum()
 }
 
-fn fix_invalid_pages(pages: &mut [Page], matrix: &Matrix) -> usize {
+/// The full rule set has cycles, so it's no total order, but each update only has to respect
+/// the rules between its own pages, and those are acyclic.
+fn fix_invalid_pages(pages: &mut [Page], order: &PartialOrder<usize>) -> usize {
     pages
         .iter_mut()
-        .filter(|p| !check_order(p, matrix))
+        .filter(|p| !order.respects(p))
         .map(|v| {
-            v.sort_by(|a, b| *matrix.get((*a, *b)).unwrap());
+            *v = order.toposort(v).unwrap_or_else(|e| panic!("{v:?}: {e}"));
             v[v.len() / 2]
         })
         .sum()
 }
 
-fn check_order(page: &[usize], matrix: &Matrix) -> bool {
-    page.is_sorted_by(|a, b| *matrix.get((*a, *b)).unwrap() == Ordering::Less)
-}
-
 pub fn solve() {
-    let input = read_to_string("inputs/day05.txt").expect("read file");
+    let input = Input::read("inputs/day05.txt");
     let (pairs, mut pages) = parse_input(&input);
-    let matrix = create_matrix(&pairs);
-    println!("Part 1: {}", valid_pages(&pages, &matrix));
-    println!("Part 2: {}", fix_invalid_pages(&mut pages, &matrix));
+    let order = PartialOrder::from_pairs(pairs);
+    if let Some(cycle) = order.find_cycle() {
+        log::debug!("rules are not a total order, {cycle}");
+    }
+    println!("Part 1: {}", valid_pages(&pages, &order));
+    println!("Part 2: {}", fix_invalid_pages(&mut pages, &order));
 }
 
 #[cfg(test)]
@@ -121,11 +97,7 @@ mod test {
 97,13,75,29,47";
 
     fn get_data() -> (Vec<Idx>, Vec<Page>) {
-        let split: Vec<&str> = TEST_INPUT.split("\n\n").collect();
-        let (pair_str, page_str) = (split[0], split[1]);
-        let pairs = parse_pairs(pair_str);
-        let pages = parse_pages(page_str);
-        (pairs, pages)
+        parse_input(TEST_INPUT)
     }
 
     #[test]
@@ -136,23 +108,25 @@ mod test {
     }
 
     #[test]
-    fn test_create_matrix() {
+    fn test_create_order() {
         let (pairs, _) = get_data();
-        let matrix = create_matrix(&pairs);
-        assert_eq!(Some(&Ordering::Less), matrix.get((97, 29)));
+        let order = PartialOrder::from_pairs(pairs);
+        assert!(order.before(97, 29));
+        assert!(!order.before(29, 97));
+        assert_eq!(None, order.find_cycle());
     }
 
     #[test]
     fn test_page_order() {
         let (pairs, pages) = get_data();
-        let matrix = create_matrix(&pairs);
-        assert_eq!(143, valid_pages(&pages, &matrix));
+        let order = PartialOrder::from_pairs(pairs);
+        assert_eq!(143, valid_pages(&pages, &order));
     }
 
     #[test]
     fn test_fix_unordered() {
         let (pairs, mut pages) = get_data();
-        let matrix = create_matrix(&pairs);
-        assert_eq!(123, fix_invalid_pages(&mut pages, &matrix))
+        let order = PartialOrder::from_pairs(pairs);
+        assert_eq!(123, fix_invalid_pages(&mut pages, &order))
     }
 }
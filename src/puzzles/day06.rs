@@ -1,6 +1,13 @@
-use std::{collections::HashSet, fs::read_to_string};
-
-use crate::shared::grid2d::Direction;
+use std::collections::HashSet;
+
+use crate::shared::{
+    cycle::brent,
+    grid2d::Direction,
+    point::Point,
+    render::{self, Frame},
+    util::Input,
+};
+use rayon::prelude::*;
 use simple_grid::{Grid, GridIndex};
 
 type Idx = (usize, usize);
@@ -13,15 +20,6 @@ enum Tile {
 }
 
 impl Direction {
-    fn turn_right(self) -> Self {
-        match self {
-            Self::Up => Self::Right,
-            Self::Right => Self::Down,
-            Self::Down => Self::Left,
-            Self::Left => Self::Up,
-        }
-    }
-
     fn get_next_idx(&self, ix: (usize, usize)) -> Option<Idx> {
         let idx = GridIndex::from(ix);
         match self {
@@ -34,14 +32,19 @@ impl Direction {
     }
 }
 
-impl std::fmt::Display for Tile {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let c = match self {
+impl From<Tile> for char {
+    fn from(tile: Tile) -> Self {
+        match tile {
             Tile::Empty => '.',
             Tile::Obstacle => '#',
             Tile::Guard => '@',
-        };
-        write!(f, "{}", c)
+        }
+    }
+}
+
+impl std::fmt::Display for Tile {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", char::from(*self))
     }
 }
 
@@ -56,122 +59,86 @@ impl From<char> for Tile {
     }
 }
 
-fn parse(input: &str) -> (Grid<Tile>, Idx) {
-    let lines = input.lines().collect::<Vec<&str>>();
-    let mut grid = Grid::new(
-        lines[0].len(),
-        lines.len(),
-        lines
-            .iter()
-            .flat_map(|l| l.chars().map(Tile::from))
-            .collect(),
-    );
+fn parse(input: &Input) -> (Grid<Tile>, Idx) {
+    let mut grid = input.grid_with(Tile::from);
     let start = grid.position(|&t| t == Tile::Guard).unwrap();
     grid.replace_cell(start, Tile::Empty);
     (grid, (start.column(), start.row()))
 }
 
-fn do_move(cur_pos: Idx, direction: &mut Direction, board: &Grid<Tile>) -> Option<Idx> {
-    if let Some(next_idx) = direction.get_next_idx(cur_pos) {
-        if let Some(tile) = board.get(GridIndex::from(next_idx)) {
-            match tile {
-                Tile::Empty => {
-                    return Some(next_idx);
-                }
-                Tile::Obstacle => {
-                    *direction = direction.turn_right();
-                    return direction.get_next_idx(cur_pos);
-                }
-                _ => panic!("oh no"),
-            }
-        } else {
-            return None;
-        }
+type Guard = Option<(Idx, Direction)>;
+
+/// One tick of the guard: turn right in place when facing an obstacle, otherwise step forward.
+/// `None` once the guard has left the board, and it stays `None` from then on.
+fn guard_step(board: &Grid<Tile>, guard: &Guard) -> Guard {
+    let (pos, dir) = (*guard)?;
+    let next = dir.get_next_idx(pos)?;
+    match board.get(next)? {
+        Tile::Obstacle => Some((pos, dir.turn_right())),
+        Tile::Empty => Some((next, dir)),
+        Tile::Guard => panic!("no other guards on the board"),
     }
-    None
 }
 
-fn walk(board: &mut Grid<Tile>, start: Idx) -> HashSet<Idx> {
-    let mut guard_pos = start;
-    let mut cur_dir = Direction::Up;
-    let mut visited = HashSet::from([guard_pos]);
-    while let Some(next_idx) = do_move(guard_pos, &mut cur_dir, board) {
-        // wait_millis(100);
-        // clear_screen();
-        // let old = board.replace_cell(guard_pos, Tile::Guard);
-        // println!("{}", board.to_pretty_string());
-        // board.replace_cell(guard_pos, old);
-        // println!("next_idx: {next_idx:?}");
-        guard_pos = next_idx;
-        visited.insert(guard_pos);
+fn guard_frame(board: &Grid<Tile>, visited: &HashSet<Idx>, guard: Idx, dir: Direction) -> Frame {
+    let to_point = |&(x, y): &Idx| Point::new(x as i32, y as i32);
+    let glyph = match dir {
+        Direction::Up => '^',
+        Direction::Right => '>',
+        Direction::Down => 'v',
+        Direction::Left => '<',
+    };
+    let mut frame = Frame::from_grid(board, |&t| t.into());
+    frame
+        .path(&visited.iter().map(to_point).collect::<Vec<_>>())
+        .agents(&[to_point(&guard)], glyph);
+    frame
+}
+
+fn walk(board: &Grid<Tile>, start: Idx) -> HashSet<Idx> {
+    let mut renderer = render::renderer();
+    let mut guard = Some((start, Direction::Up));
+    let mut visited = HashSet::from([start]);
+    while let Some((pos, dir)) = guard_step(board, &guard) {
+        guard = Some((pos, dir));
+        if visited.insert(pos) {
+            if let Some(renderer) = renderer.as_mut().filter(|r| !r.is_closed()) {
+                let frame = guard_frame(board, &visited, pos, dir);
+                renderer
+                    .draw(&frame, &format!("visited: {}", visited.len()))
+                    .expect("write frame");
+            }
+        }
     }
     visited
 }
 
-#[allow(unused)]
+/// Once off the board the guard sits in the fixed point `None`, a cycle of length one. Any
+/// longer cycle means they are stuck walking a loop.
 fn detect_loop_walk(board: &Grid<Tile>, start: Idx, wall: Idx) -> bool {
     let mut board_clone = board.clone();
     board_clone.replace_cell(wall, Tile::Obstacle);
-    let mut guard_pos = start;
-    let mut cur_dir = Direction::Up;
-    let mut visited = HashSet::from([(start, cur_dir)]);
-    loop {
-        // wait_millis(10);
-        // clear_screen();
-        // let old = board_clone.replace_cell(guard_pos, Tile::Guard);
-        // println!("{}", board_clone.to_pretty_string());
-        // board_clone.replace_cell(guard_pos, old);
-        if let Some(pos) = cur_dir.get_next_idx(guard_pos) {
-            match board_clone.get(pos) {
-                Some(Tile::Obstacle) => {
-                    // Check if we've faced this exact obstacle before
-                    if visited.contains(&(pos, cur_dir)) {
-                        break true;
-                    }
-                    // only need to store collisions with obstacles
-                    visited.insert((pos, cur_dir));
-                    cur_dir = cur_dir.turn_right();
-                }
-                Some(Tile::Empty) => {
-                    guard_pos = pos;
-                    visited.insert((pos, cur_dir));
-                }
-                Some(Tile::Guard) => {
-                    panic!("no other guards on the board");
-                }
-                // Guard has left area
-                None => {
-                    break false;
-                }
-            }
-        } else {
-            break false;
-        }
-    }
+    let cycle = brent(&Some((start, Direction::Up)), |guard| {
+        guard_step(&board_clone, guard)
+    });
+    cycle.len > 1
 }
 
-#[allow(unused)]
-fn part2(board: &Grid<Tile>, start: Idx) -> usize {
-    println!("Board: {}, {}", board.width(), board.height());
-    board
-        .indices()
-        .filter(|grid_index| {
-            let pos = (grid_index.column(), grid_index.row());
-            // println!("trying {pos:?}");
-            pos.0!= start.0 && pos.1 != start.1 // don't check original guard start
-            && board.get(pos).unwrap() == &Tile::Empty
-            && detect_loop_walk(board, start, pos)
-        })
+/// A new obstacle only changes anything if the guard would have walked into it, so only the
+/// cells from part 1 are worth trying.
+fn part2(board: &Grid<Tile>, start: Idx, visited: &HashSet<Idx>) -> usize {
+    visited
+        .par_iter()
+        .filter(|&&pos| pos != start && detect_loop_walk(board, start, pos))
         .count()
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day06.txt").expect("file read error");
-    let (mut board, start) = parse(&input);
-    let visited = walk(&mut board, start);
+    let input = Input::read("inputs/day06.txt");
+    let (board, start) = parse(&input);
+    let visited = walk(&board, start);
     println!("Part 1: {}", visited.len());
-    // println!("Part 2: {}", part2(&board, start));
-    println!("Part 2: TOO_SLOW");
+    println!("Part 2: {}", part2(&board, start, &visited));
 }
 
 #[cfg(test)]
@@ -192,21 +159,22 @@ mod test {
 
     #[test]
     fn test_parse() {
-        let (board, _) = parse(TEST_INPUT);
+        let (board, _) = parse(&Input::from(TEST_INPUT));
         assert_eq!(10, board.width());
     }
 
     #[test]
     fn test_walk() {
-        let (mut board, start) = parse(TEST_INPUT);
-        let visited = walk(&mut board, start);
+        let (board, start) = parse(&Input::from(TEST_INPUT));
+        let visited = walk(&board, start);
         assert_eq!(41, visited.len());
     }
 
     #[test]
     fn test_detect_loops() {
-        let (board, start) = parse(TEST_INPUT);
-        let loops = part2(&board, start);
+        let (board, start) = parse(&Input::from(TEST_INPUT));
+        let visited = walk(&board, start);
+        let loops = part2(&board, start, &visited);
         assert_eq!(6, loops);
     }
 }
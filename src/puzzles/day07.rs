@@ -1,17 +1,12 @@
 use itertools::{repeat_n, Itertools};
-use std::{collections::HashMap, fs::read_to_string};
+use std::collections::HashMap;
+
+use crate::shared::{parse::key_values, util::Input};
 
 fn parse_input(input: &str) -> HashMap<usize, Vec<usize>> {
-    input
-        .lines()
-        .filter_map(|l| l.split_once(':'))
-        .map(|(val, rest)| {
-            let digits = rest
-                .split_whitespace()
-                .filter_map(|s| s.parse().ok())
-                .collect();
-            (val.parse().unwrap(), digits)
-        })
+    key_values(input)
+        .unwrap_or_else(|e| panic!("{e}"))
+        .into_iter()
         .collect()
 }
 
@@ -19,7 +14,7 @@ fn parse_input(input: &str) -> HashMap<usize, Vec<usize>> {
 /// '|']` operators and try them in order. If any series of operations performed on the vec of
 /// `[value, ...]`s produce the `k`, we keep the `k` and sum the resulting `vec[k]`.
 /// Found a neat trick to use the base 10 logarithm of a number to get the number of digits:
-/// ```
+/// ```text
 /// //Ex: x = 45, acc = 123
 /// let num_digits = x.checked_ilog10().unwrap() + 1 // 2
 /// let multiplier = 10usize.pow(num_digits) // 100
@@ -49,7 +44,7 @@ fn calibrate(map: &HashMap<usize, Vec<usize>>, ops: &[char]) -> usize {
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day07.txt").expect("file open error");
+    let input = Input::read("inputs/day07.txt");
     let map = parse_input(&input);
     println!("Part 1: {}", calibrate(&map, &['+', 'x']));
     println!("Part 2: {}", calibrate(&map, &['+', 'x', '|']));
@@ -1,14 +1,17 @@
-use crate::shared::point::Point;
-use std::{
-    collections::{HashMap, HashSet},
-    fs::read_to_string,
+use crate::shared::{
+    lattice::{line_through_raw, ray},
+    point::Point,
+    sparse_grid::SparseGrid,
+    util::Input,
 };
+use itertools::Itertools;
+use std::collections::{HashMap, HashSet};
 
 fn parse_input(input: &str) -> (HashMap<char, Vec<Point>>, Point) {
-    let max_x = input.trim().lines().peekable().peek().unwrap().len() - 1;
-    let max_y = input.trim().lines().count() - 1;
+    let max_x = input.lines().next().unwrap().len() - 1;
+    let max_y = input.lines().count() - 1;
     let mut map: HashMap<char, Vec<Point>> = HashMap::new();
-    input.trim().lines().enumerate().for_each(|(y, l)| {
+    input.lines().enumerate().for_each(|(y, l)| {
         l.chars().enumerate().for_each(|(x, c)| match c {
             '.' => {}
             _ => {
@@ -20,42 +23,40 @@ fn parse_input(input: &str) -> (HashMap<char, Vec<Point>>, Point) {
     (map, (max_x as i32, max_y as i32).into())
 }
 
+/// The two antinodes of a pair: one step of `pt2 - pt1` beyond each antenna
 fn get_anodes(pt1: Point, pt2: Point, max: Point) -> HashSet<Point> {
-    let (dx, dy) = (pt1.x - pt2.x, pt2.y - pt1.y);
-    let (new_pt1, new_pt2) = if dy == 0 {
-        (Point::new(pt1.x - dx, pt1.y), Point::new(pt2.x + dx, pt2.y))
-    } else if dx == 0 {
-        (Point::new(pt1.x, pt1.y - dy), Point::new(pt2.x, pt2.y + dy))
-    } else {
-        (
-            Point::new(pt1.x + dx, pt1.y - dy),
-            Point::new(pt2.x - dx, pt2.y + dy),
-        )
-    };
-    let set = [new_pt1, new_pt2]
-        .iter()
-        .filter_map(|&p| p.in_bounds((0, 0).into(), max).then_some(p))
-        .collect();
-    set
+    let bounds = (Point::new(0, 0), max);
+    [(pt1, pt1 - pt2), (pt2, pt2 - pt1)]
+        .into_iter()
+        .filter_map(|(antenna, step)| ray(antenna, step, &bounds).nth(1))
+        .collect()
 }
 
-fn part1(map: &HashMap<char, Vec<Point>>, max: &Point) -> usize {
-    let mut anodes = HashSet::new();
-    for (_, v) in map.iter() {
-        for i in 0..v.len() - 1 {
-            for j in i + 1..v.len() {
-                anodes.extend(get_anodes(v[i], v[j], *max))
-            }
+/// Resonant harmonics: every point in line with the pair at a whole multiple of their distance,
+/// antennas included
+fn get_harmonics(pt1: Point, pt2: Point, max: Point) -> HashSet<Point> {
+    line_through_raw(pt1, pt2, &(Point::new(0, 0), max)).collect()
+}
+
+fn count_antinodes<F>(map: &HashMap<char, Vec<Point>>, max: &Point, antinodes: F) -> usize
+where
+    F: Fn(Point, Point, Point) -> HashSet<Point>,
+{
+    let mut anodes = SparseGrid::new();
+    for v in map.values() {
+        for (&a, &b) in v.iter().tuple_combinations() {
+            anodes.extend(antinodes(a, b, *max).into_iter().map(|pt| (pt, '#')))
         }
     }
+    log::debug!("antinodes:\n{anodes}");
     anodes.len()
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day08.txt").expect("file read err");
+    let input = Input::read("inputs/day08.txt");
     let (map, max) = parse_input(&input);
-    println!("Part 1: {}", part1(&map, &max));
-    println!("Part 2: TODO!");
+    println!("Part 1: {}", count_antinodes(&map, &max, get_anodes));
+    println!("Part 2: {}", count_antinodes(&map, &max, get_harmonics));
 }
 
 #[cfg(test)]
@@ -95,11 +96,14 @@ mod test {
     #[test]
     fn test_part1() {
         let (map, max) = parse_input(TEST_INPUT);
-        let res = part1(&map, &max);
+        let res = count_antinodes(&map, &max, get_anodes);
         assert_eq!(res, 14);
     }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        let (map, max) = parse_input(TEST_INPUT);
+        let res = count_antinodes(&map, &max, get_harmonics);
+        assert_eq!(res, 34);
+    }
 }
@@ -1,9 +1,7 @@
-use std::fs::read_to_string;
+use crate::shared::util::Input;
 
-fn parse_input(input: &str) -> Vec<usize> {
+fn parse_input(input: &Input) -> Vec<usize> {
     input
-        .strip_suffix("\n")
-        .unwrap()
         .chars()
         .map(|c| c.to_digit(10).expect("should be a digit") as usize)
         .collect()
@@ -91,7 +89,7 @@ fn checksum(compressed_disk: &[usize]) -> usize {
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day09.txt").expect("read file");
+    let input = Input::read("inputs/day09.txt");
     let disk_map = parse_input(&input);
     let mut disk = expand(&disk_map);
     let start = disk_map[0];
@@ -110,7 +108,7 @@ mod test {
 
     #[test]
     fn test_expand() {
-        let disk_map = parse_input(TEST_INPUT);
+        let disk_map = parse_input(&Input::from(TEST_INPUT));
         let expanded = expand(&disk_map);
         assert_eq!(
             expanded,
@@ -127,9 +125,9 @@ mod test {
 
     #[test]
     fn test_compress() {
-        let disk_map = parse_input(TEST_INPUT);
+        let disk_map = parse_input(&Input::from(TEST_INPUT));
         let mut expanded = expand(&disk_map);
-        compress(&mut expanded, disk_map[0] as usize);
+        compress(&mut expanded, disk_map[0]);
         assert_eq!(
             expanded,
             "0099811188827773336446555566.............."
@@ -146,7 +144,7 @@ mod test {
 
     #[test]
     fn test_compress_v2() {
-        let disk_map = parse_input(TEST_INPUT);
+        let disk_map = parse_input(&Input::from(TEST_INPUT));
         let mut disk = expand(&disk_map);
         println!("DISK: {disk:?}");
         wait_millis(1000);
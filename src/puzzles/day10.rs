@@ -1,29 +1,19 @@
-use std::fs::read_to_string;
-
 use petgraph::{
     algo::{all_simple_paths, astar},
     graph::NodeIndex,
     Graph,
 };
-use simple_grid::Grid;
+
+use crate::shared::util::Input;
 
 fn parse_input(
-    input: &str,
+    input: &Input,
 ) -> (
     Graph<u32, u32, petgraph::Directed>,
     Vec<NodeIndex>,
     Vec<NodeIndex>,
 ) {
-    let width = input.lines().next().unwrap().len();
-    let height = input.len() / width;
-    let grid = Grid::new(
-        width,
-        height,
-        input
-            .lines()
-            .flat_map(|l| l.chars().map(|c| c.to_digit(10).unwrap()))
-            .collect(),
-    );
+    let grid = input.grid_with(|c| c.to_digit(10).unwrap());
     let mut zero_indices = vec![];
     let mut nine_indices = vec![];
     let mut graph = Graph::new();
@@ -82,8 +72,8 @@ fn part2(graph: &Graph<u32, u32>, zeroes: &[NodeIndex], nines: &[NodeIndex]) ->
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day10.txt").expect("error opening file");
-    let (graph, zeroes, nines) = parse_input(input.strip_suffix("\n").unwrap());
+    let input = Input::read("inputs/day10.txt");
+    let (graph, zeroes, nines) = parse_input(&input);
     println!("Part 1: {}", part1(&graph, &zeroes, &nines));
     println!("Part 2: {}", part2(&graph, &zeroes, &nines));
 }
@@ -107,26 +97,26 @@ mod test {
     #[test]
     fn test_parse() {
         let char_count = TEST_INPUT.lines().flat_map(|l| l.chars()).count();
-        let (graph, _, _) = parse_input(TEST_INPUT);
+        let (graph, _, _) = parse_input(&Input::from(TEST_INPUT));
         assert_eq!(graph.node_count(), char_count);
     }
 
     #[test]
     fn test_astar() {
-        let (graph, zeroes, nines) = parse_input(TEST_INPUT);
+        let (graph, zeroes, nines) = parse_input(&Input::from(TEST_INPUT));
         assert!(astar(&graph, zeroes[0], |f| f == nines[0], |e| *e.weight(), |_| 9).is_some());
         assert!(astar(&graph, zeroes[0], |f| f == nines[1], |e| *e.weight(), |_| 9).is_none());
     }
 
     #[test]
     fn test_part1() {
-        let (graph, zeroes, nines) = parse_input(TEST_INPUT);
+        let (graph, zeroes, nines) = parse_input(&Input::from(TEST_INPUT));
         assert_eq!(36, part1(&graph, &zeroes, &nines));
     }
 
     #[test]
     fn test_part2() {
-        let (graph, zeroes, nines) = parse_input(TEST_INPUT);
+        let (graph, zeroes, nines) = parse_input(&Input::from(TEST_INPUT));
         assert_eq!(81, part2(&graph, &zeroes, &nines));
     }
 }
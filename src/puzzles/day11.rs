@@ -1,14 +1,11 @@
-use std::collections::HashMap;
+use crate::shared::counter::Counter;
 
-/// Create HashMap of present stone values
-fn parse_input(input: &str) -> HashMap<u64, usize> {
+/// Count the stones of each value
+fn parse_input(input: &str) -> Counter<u64> {
     input
         .split_whitespace()
         .filter_map(|s| s.parse().ok())
-        .fold(HashMap::new(), |mut acc, x| {
-            *acc.entry(x).or_insert(0) += 1;
-            acc
-        })
+        .collect()
 }
 
 fn split_stone(stone: u64, len: u32) -> (u64, u64) {
@@ -18,35 +15,35 @@ fn split_stone(stone: u64, len: u32) -> (u64, u64) {
     (left, right)
 }
 
-fn blink(stones: &mut HashMap<u64, usize>) -> HashMap<u64, usize> {
-    let mut new_stones = HashMap::new();
-    stones.iter_mut().for_each(|(&stone, count)| {
-        if stone == 0 {
-            *new_stones.entry(1).or_insert(0) += *count;
-        } else {
-            let num_digits = stone.checked_ilog10().unwrap_or(0) + 1;
-            if num_digits % 2 == 0 {
-                let (l, r) = split_stone(stone, num_digits);
-                *new_stones.entry(l).or_insert(0) += *count;
-                *new_stones.entry(r).or_insert(0) += *count;
-            } else {
-                *new_stones.entry(stone * 2024).or_insert(0) += *count;
-            }
-        }
-    });
-    new_stones
+/// What a single stone turns into after one blink
+fn evolve(stone: u64) -> impl Iterator<Item = u64> {
+    let num_digits = stone.checked_ilog10().unwrap_or(0) + 1;
+    let stones = if stone == 0 {
+        [Some(1), None]
+    } else if num_digits.is_multiple_of(2) {
+        let (l, r) = split_stone(stone, num_digits);
+        [Some(l), Some(r)]
+    } else {
+        [Some(stone * 2024), None]
+    };
+    stones.into_iter().flatten()
+}
+
+/// Stones with the same value always evolve the same way, so only the count per value matters
+fn blink(stones: Counter<u64>) -> Counter<u64> {
+    stones.flat_map_keys(evolve)
 }
 
 pub fn solve() {
     let mut stones = parse_input("5178527 8525 22 376299 3 69312 0 275");
     for _ in 0..25 {
-        stones = blink(&mut stones);
+        stones = blink(stones);
     }
-    println!("Part 1: {}", stones.values().sum::<usize>());
+    println!("Part 1: {}", stones.total());
     for _ in 0..50 {
-        stones = blink(&mut stones);
+        stones = blink(stones);
     }
-    println!("Part 2: {}", stones.values().sum::<usize>());
+    println!("Part 2: {}", stones.total());
 }
 
 #[cfg(test)]
@@ -59,8 +56,8 @@ mod test {
     fn test_25_blinks() {
         let mut stones = parse_input(TEST_INPUT);
         for _ in 0..25 {
-            stones = blink(&mut stones);
+            stones = blink(stones);
         }
-        assert_eq!(stones.values().sum::<usize>(), 55312);
+        assert_eq!(stones.total(), 55312);
     }
 }
@@ -1,145 +1,70 @@
-use std::{
-    collections::{HashSet, VecDeque},
-    fs::read_to_string,
-};
-
-use itertools::Itertools;
-use simple_grid::{Grid, GridIndex};
+use simple_grid::Grid;
 
-use crate::shared::point::Point;
+use crate::shared::{
+    image::{self, Format, ImageExport, Palette},
+    regions::{connected_components, Components},
+    render::Frame,
+    util::Input,
+};
 
-fn parse_input(input: &str) -> Grid<char> {
-    let height = input.lines().count();
-    let width = input.lines().next().unwrap().chars().count();
-    Grid::new(
-        width,
-        height,
-        input.lines().flat_map(|line| line.chars()).collect_vec(),
-    )
+fn parse_input(input: &Input) -> Grid<char> {
+    input.grid()
 }
-fn get_neighbors(pos: GridIndex, grid: &Grid<char>, plant: char) -> Vec<GridIndex> {
-    grid.cardinal_neighbor_indices_of(pos)
-        .filter(|&idx| grid.get(idx).is_some_and(|&p| p == plant))
-        .collect_vec()
-}
-fn bfs(
-    pos: GridIndex,
-    visited: &mut HashSet<GridIndex>,
-    grid: &Grid<char>,
-) -> (HashSet<GridIndex>, usize) {
-    let mut queue = VecDeque::new();
-    let plant = grid.get(pos).unwrap();
-    let mut area = HashSet::default();
-    let mut perimeter = 0;
-    visited.insert(pos);
-    queue.push_back(pos);
-    while !queue.is_empty() {
-        let curr = queue.pop_front().unwrap();
-        area.insert(curr);
-        let neighbors = get_neighbors(curr, grid, *plant);
-        perimeter += 4 - neighbors.len(); // gets only exterior edges
-        for nbr in neighbors {
-            if !visited.contains(&nbr) {
-                visited.insert(nbr);
-                queue.push_back(nbr);
-            }
-        }
+
+fn plots(grid: &Grid<char>) -> Components<char> {
+    let plots = connected_components(grid, |a, b| a == b);
+    for region in &plots.regions {
+        let (min, max) = region.bounding_box();
+        log::debug!(
+            "region {} spans {min} to {max}: area {}, perimeter {}, sides {}\n{}",
+            region.label,
+            region.area(),
+            region.perimeter(),
+            region.sides(),
+            region.cells
+        );
     }
-    (area, perimeter)
+    plots
 }
 
-fn get_price_and_regions(grid: &Grid<char>, regions: &mut Vec<HashSet<Point>>) -> usize {
-    let mut visited = HashSet::default();
-    let mut price = 0;
-    grid.columns()
-        .cartesian_product(&grid.rows().collect_vec())
-        .map(|(x, &y)| GridIndex::from((x, y)))
-        .for_each(|idx| {
-            if !visited.contains(&idx) {
-                let (area, perim) = bfs(idx, &mut visited, grid);
-                price += area.len() * perim;
-                regions.push(area.iter().map(|&idx| Point::from(idx)).collect());
-            }
-        });
-    price
+fn fence_price(plots: &Components<char>) -> usize {
+    plots
+        .regions
+        .iter()
+        .map(|region| region.area() * region.perimeter())
+        .sum()
 }
 
-/// Get all sides by checking one direction at a time
-/// ```no_run
-/// (0, -1), (1, 0), (0, 1) (-1, 0)
-///   up     right    down   left
-/// ```
-/// Example for 'R':
-/// ```no_run
-/// RRRRII we check first  ^^^^II
-/// RRRRII by looking up   ^^^^II
-/// VVRRRC like so:        VV^^^C
-/// VVRCCC                 VV^CCC
-/// VVVVCJ                 VVVVCJ
-/// ```
-///
-/// We record all positions that are not in the region
-/// ```no_run
-/// (0, -1), (1, -1), (2, -1), (3, -1), (4, 2)
-/// ```
-/// Then, for each of those positions, we collect sides facing up by looking
-/// counter-clockwise to the current direction, and removing all but the top
-/// corner of a face/side.
-/// ```no_run
-/// to_remove = /* HashSet */ [(0, -1), (1, -1), (2, -1)];
-/// sides.len() /* 5 */ - remove.len() /* 3 */ = 2;
-/// ```
-/// so we have collected the two top-facing sides, and move on to the next
-fn count_region_sides(region: &HashSet<Point>) -> usize {
-    let mut side_count = 0;
-    for direction in Point::new(0, 0).cardinal_neighbors() {
-        // println!("Direction: {direction}");
-        let mut sides = HashSet::new();
-        for pos in region {
-            let pt = *pos + direction;
-            if !region.contains(&pt) {
-                sides.insert(pt);
-            }
-        }
-        let mut to_remove = HashSet::new();
-        log::debug!(
-            "all side pieces: {}",
-            sides
-                .iter()
-                .sorted_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)))
-                .map(|s| format!("{s}"))
-                .collect_vec()
-                .join(" ")
-        );
-        for side in sides
-            .iter()
-            .sorted_by(|a, b| a.x.cmp(&b.x).then(a.y.cmp(&b.y)))
-        {
-            log::debug!("  checking side: {side}");
-            let mut next = (side.x + direction.y, side.y + direction.x).into();
-            while sides.contains(&next) {
-                log::debug!("    next: {next}");
-                to_remove.insert(next);
-                next = (next.x + direction.y, next.y + direction.x).into();
-            }
-        }
-        log::debug!("  to_remove: {to_remove:?}");
-        side_count += sides.len() - to_remove.len();
-    }
-    side_count
+fn bulk_fence_price(plots: &Components<char>) -> usize {
+    plots
+        .regions
+        .iter()
+        .map(|region| region.area() * region.sides())
+        .sum()
+}
+
+/// One glyph per region rather than per plant, so separate regions growing the same plant still
+/// get their own colour from [`Palette::auto`]. The glyphs come from the Unicode private use
+/// area, which has room for far more regions than a puzzle input holds.
+fn region_frame(plots: &Components<char>) -> Frame {
+    Frame::from_fn(plots.width, plots.height, |pt| {
+        let label = plots.label_of(pt).expect("frame matches the grid");
+        char::from_u32(0xE000 + label as u32).unwrap_or('?')
+    })
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day12.txt").expect("file read err");
+    let input = Input::read("inputs/day12.txt");
     let grid = parse_input(&input);
-    let mut regions = Vec::new();
-    let price_pt1 = get_price_and_regions(&grid, &mut regions);
-    println!("Part 1: {}", price_pt1);
-    let price_pt2: usize = regions
-        .iter_mut()
-        .map(|region| region.len() * count_region_sides(region))
-        .sum();
-    println!("Part 2: {}", price_pt2);
+    let plots = plots(&grid);
+    println!("Part 1: {}", fence_price(&plots));
+    println!("Part 2: {}", bulk_fence_price(&plots));
+    let export = ImageExport::new(Format::Ppm).palette(Palette::auto());
+    if let Some(mut frames) = image::exporter("day12", export) {
+        let frame = region_frame(&plots);
+        let path = frames.write(&frame).expect("write image");
+        println!("Regions written to {}", path.display());
+    }
 }
 
 #[cfg(test)]
@@ -163,48 +88,43 @@ MMMISSJEEE
     #[test]
     fn test_parse() {
         test_setup();
-        let grid = parse_input(TEST_INPUT);
-        assert_eq!(grid.get(GridIndex::from((1, 2))), Some(&'V'));
+        let grid = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(grid.get((1, 2)), Some(&'V'));
     }
 
     #[test]
     fn test_bfs_part1() {
         test_setup();
-        let grid = parse_input(TEST_INPUT);
-        assert_eq!(get_price_and_regions(&grid, &mut vec![]), 1930);
+        let grid = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(fence_price(&plots(&grid)), 1930);
     }
 
     #[test]
     fn test_bfs_part2() {
         test_setup();
-        let grid = parse_input(TEST_INPUT);
-        let mut regions = Vec::new();
-        _ = get_price_and_regions(&grid, &mut regions);
-        let price_r_region = regions
-            .into_iter()
-            .find(|r| {
-                let mut peekable = r.iter().peekable();
-                let pt = peekable.peek().unwrap();
-                let idx: GridIndex = (pt.x as usize, pt.y as usize).into();
-                let c = grid.get(idx);
-                c == Some(&'R')
-            })
-            .unwrap();
-        assert_eq!(count_region_sides(&price_r_region), 10);
+        let grid = parse_input(&Input::from(TEST_INPUT));
+        let plots = plots(&grid);
+        let price_r_region = plots.region_at((0, 0).into()).unwrap();
+        assert_eq!(price_r_region.sides(), 10);
     }
 
     #[test]
     fn test_part2_total() {
         test_setup();
-        let grid = parse_input(TEST_INPUT);
-        let mut regions = Vec::new();
-        _ = get_price_and_regions(&grid, &mut regions);
-        assert_eq!(
-            1206,
-            regions
-                .iter_mut()
-                .map(|region| region.len() * count_region_sides(region))
-                .sum::<usize>()
-        )
+        let grid = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(1206, bulk_fence_price(&plots(&grid)));
+    }
+
+    #[test]
+    fn test_region_frame() {
+        test_setup();
+        let grid = parse_input(&Input::from(TEST_INPUT));
+        let frame = region_frame(&plots(&grid));
+        let glyph = |x: usize, y: usize| frame.cells()[y * frame.width + x].glyph;
+        // the two I regions share a plant but not a colour
+        assert_eq!(glyph(4, 0), glyph(5, 1));
+        assert_ne!(glyph(4, 0), glyph(2, 5));
+        let palette = Palette::auto();
+        assert_ne!(palette.get(glyph(4, 0)), palette.get(glyph(2, 5)));
     }
 }
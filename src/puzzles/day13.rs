@@ -1,48 +1,41 @@
-use std::fs::read_to_string;
+use crate::shared::{
+    linear::min_cost_2d,
+    parse::{self, sections, ParseError},
+    util::Input,
+};
 
-use regex::Regex;
+type Point = (i128, i128);
 
-type Point = (f64, f64);
-
-fn parse_input(input: &str) -> Vec<(Point, Point, Point)> {
-    let re = Regex::new(r"[A-Z\s:\+=]+(?<x>\d+),\sY[\+\=](?<y>\d+)").expect("invalid regex");
-    input
-        .split("\n\n")
-        .map(|section| {
-            section
-                .lines()
-                .filter_map(|line| {
-                    let (_, [x, y]) = re.captures(line).map(|caps| caps.extract())?;
-                    Some((x.parse().unwrap(), y.parse().unwrap()))
-                })
-                .collect::<Vec<_>>()
+fn parse_input(input: &str) -> parse::Result<Vec<(Point, Point, Point)>> {
+    sections(input)
+        .iter()
+        .map(|section| match section.ints()?[..] {
+            [ax, ay, bx, by, px, py] => Ok(((ax, ay), (bx, by), (px, py))),
+            _ => Err(ParseError {
+                line: section.first_line,
+                text: section.text.to_owned(),
+                message: "expected a claw machine".to_owned(),
+            }),
         })
-        .map(|v| (v[0], v[1], v[2]))
         .collect()
 }
 
-fn get_tokens(scenarios: &[(Point, Point, Point)], pos_mod: f64) -> f64 {
-    let mut total = 0.0;
-    for (a, b, prize) in scenarios {
-        let ((ax, ay), (bx, by), (px, py)) = (a, b, prize);
-        let a_perp_dot_p = ay * (px + pos_mod) - ax * (py + pos_mod);
-        let a_perp_dot_b = ay * bx - ax * by;
-        let (y, remainder) = (a_perp_dot_p / a_perp_dot_b, a_perp_dot_p % a_perp_dot_b);
-        if remainder == 0.0 {
-            let x = ((px + pos_mod) - bx * y) / ax;
-            if x.fract() == 0.0 {
-                total += 3.0 * x + y;
-            }
-        }
-    }
-    total
+/// Each machine is `a * A + b * B == prize`, solved exactly in integers. A press of A costs 3
+/// tokens and B costs 1, which only matters when A and B point the same way and there's more
+/// than one way to reach the prize.
+fn get_tokens(scenarios: &[(Point, Point, Point)], pos_mod: i128) -> i128 {
+    scenarios
+        .iter()
+        .filter_map(|&(a, b, (px, py))| min_cost_2d(a, b, (px + pos_mod, py + pos_mod), (3, 1)))
+        .map(|(a_presses, b_presses)| 3 * a_presses + b_presses)
+        .sum()
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day13.txt").expect("error reading file");
-    let scenarios = parse_input(&input);
-    println!("Part 1: {}", get_tokens(&scenarios, 0.0));
-    println!("Part 2: {}", get_tokens(&scenarios, 10_000_000_000_000.0));
+    let input = Input::read("inputs/day13.txt");
+    let scenarios = parse_input(&input).unwrap_or_else(|e| panic!("Error reading input: {e}"));
+    println!("Part 1: {}", get_tokens(&scenarios, 0));
+    println!("Part 2: {}", get_tokens(&scenarios, 10_000_000_000_000));
 }
 
 #[cfg(test)]
@@ -68,13 +61,24 @@ Prize: X=18641, Y=10279";
 
     #[test]
     fn test_parse() {
-        let scenarios = parse_input(TEST_INPUT);
+        let scenarios = parse_input(TEST_INPUT).unwrap();
         assert_eq!(scenarios.len(), 4);
     }
     #[test]
     fn test_solve() {
-        let scenarios = parse_input(TEST_INPUT);
-        let part1 = get_tokens(&scenarios, 0.0);
-        assert_eq!(part1, 480.0);
+        let scenarios = parse_input(TEST_INPUT).unwrap();
+        let part1 = get_tokens(&scenarios, 0);
+        assert_eq!(part1, 480);
+    }
+
+    #[test]
+    fn test_offset_prizes() {
+        let scenarios = parse_input(TEST_INPUT).unwrap();
+        // only the second and fourth machines can win once the prizes move
+        let winnable = scenarios
+            .iter()
+            .map(|s| get_tokens(&[*s], 10_000_000_000_000) > 0)
+            .collect::<Vec<_>>();
+        assert_eq!(winnable, [false, true, false, true]);
     }
 }
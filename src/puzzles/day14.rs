@@ -1,8 +1,23 @@
 use itertools::Itertools;
-use std::{cmp::Ordering, collections::HashMap, env, fs::read_to_string};
+use nom::{
+    bytes::complete::tag,
+    character::complete::space1,
+    combinator::map,
+    sequence::{preceded, separated_pair},
+    IResult,
+};
+use std::cmp::Ordering;
 
-use crate::shared::{grid2d::FlatGrid, point::Point};
-use regex::Regex;
+use crate::shared::{
+    bit_grid::BitGrid,
+    counter::Counter,
+    image::{self, Format, ImageExport},
+    math::crt,
+    parse::{self, coord, parse_lines},
+    point::Point,
+    render::{self, Frame},
+    util::Input,
+};
 
 const BOUNDS: Point = Point { x: 101, y: 103 };
 
@@ -33,23 +48,20 @@ impl From<(Point, Point)> for Robot {
     }
 }
 
-fn parse_input(input: &str) -> Vec<Robot> {
-    let re = Regex::new(r"^p\=(?<x>-?\d+),(?<y>-?\d+) v\=(?<vx>-?\d+),(?<vy>-?\d+)$")
-        .expect("invalid regex");
-    input
-        .lines()
-        .filter_map(|line| {
-            re.captures(line)
-                .map(|caps| caps.extract())
-                .map(|(_, [x, y, vx, vy])| {
-                    (
-                        (x.parse().unwrap(), y.parse().unwrap()),
-                        (vx.parse().unwrap(), vy.parse().unwrap()),
-                    )
-                        .into()
-                })
-        })
-        .collect()
+/// `p=x,y v=dx,dy`
+fn robot(input: &str) -> IResult<&str, Robot> {
+    map(
+        separated_pair(
+            preceded(tag("p="), coord),
+            space1,
+            preceded(tag("v="), coord),
+        ),
+        Robot::from,
+    )(input)
+}
+
+fn parse_input(input: &str) -> parse::Result<Vec<Robot>> {
+    parse_lines(input, robot)
 }
 
 fn move_robot(robot: &Robot, bounds: Point, seconds: i32) -> Point {
@@ -60,16 +72,11 @@ fn move_robot(robot: &Robot, bounds: Point, seconds: i32) -> Point {
         .into()
 }
 
-fn move_all_robots(robots: &[Robot], bounds: Point, seconds: i32) -> HashMap<Point, usize> {
-    let mut pos_counts: HashMap<Point, usize> = HashMap::new();
-    for robot in robots {
-        let moved = move_robot(robot, bounds, seconds);
-        pos_counts
-            .entry(moved)
-            .and_modify(|cnt| *cnt += 1)
-            .or_insert(1);
-    }
-    pos_counts
+fn move_all_robots(robots: &[Robot], bounds: Point, seconds: i32) -> Counter<Point> {
+    robots
+        .iter()
+        .map(|robot| move_robot(robot, bounds, seconds))
+        .collect()
 }
 
 fn part1(robots: &[Robot], bounds: Point, seconds: i32) -> usize {
@@ -85,85 +92,89 @@ fn part1(robots: &[Robot], bounds: Point, seconds: i32) -> usize {
     {
         product *= pos_counts
             .iter()
-            .filter_map(|(&k, v)| (k.x.cmp(x) == x_ord && k.y.cmp(y) == y_ord).then_some(v))
+            .filter_map(|(k, v)| (k.x.cmp(x) == x_ord && k.y.cmp(y) == y_ord).then_some(v))
             .sum::<usize>();
     }
     product
 }
 
-fn part2(robots: &[Robot], bounds: Point) -> i32 {
-    // Stolen from someone elses answer :'(
-    use rayon::prelude::*;
-    if let Some(seconds) = (100..(bounds.x * bounds.y))
-        .into_par_iter()
-        .find_first(|t| {
-            let mut grid = vec![0_u128; bounds.y as usize];
-
-            for robot in robots {
-                let (x, y) = (
-                    (robot.point.x + robot.vector.x * t).rem_euclid(bounds.x) as usize,
-                    (robot.point.y + robot.vector.y * t).rem_euclid(bounds.y) as usize,
-                );
-
-                grid[y] |= 1 << x;
-            }
-
-            // looking to see 2 rows with a continous line of at least 16 robots
-            // this will match the lines above and below the christmas tree
-            grid.iter()
-                .filter(|&row| {
-                    (0..bounds.x)
-                        .step_by(16)
-                        .map(|x| 0xFFFF << x)
-                        .any(|mask| row & mask == mask)
-                })
-                .take(2)
-                .count()
-                >= 2
-        })
-    {
-        seconds
-    } else {
-        panic!("no solution")
-    }
+/// Sum of squared distances from the mean along one axis, scaled by the robot count to stay in
+/// integers
+fn spread(robots: &[Robot], bounds: Point, seconds: i32, axis: fn(Point) -> i32) -> i64 {
+    let (sum, sum_sq) = robots
+        .iter()
+        .map(|r| axis(move_robot(r, bounds, seconds)) as i64)
+        .fold((0, 0), |(sum, sum_sq), v| (sum + v, sum_sq + v * v));
+    robots.len() as i64 * sum_sq - sum * sum
 }
 
-fn flat_grid_from_robots(robots: &[Robot], width: usize, height: usize) -> FlatGrid<char> {
-    let mut grid = FlatGrid {
-        width,
-        height,
-        data: vec!['.'; width * height],
-    };
+/// The tree is drawn inside a frame, so at least two rows hold a run of 16 or more robots
+fn shows_tree(robots: &[Robot], bounds: Point, seconds: i32) -> bool {
+    let mut grid = BitGrid::new(bounds.x as usize, bounds.y as usize);
     for robot in robots {
-        grid.replace_cell(robot.point.x as usize, robot.point.y as usize, '0');
+        let pt = move_robot(robot, bounds, seconds);
+        grid.set(pt.x as usize, pt.y as usize);
     }
-    grid
+    (0..grid.height)
+        .filter(|&y| grid.longest_row_run(y) >= 16)
+        .take(2)
+        .count()
+        >= 2
+}
+
+/// The robots bunch up to draw the tree, so the x positions are least spread out at one time
+/// modulo the width, and the y positions at one time modulo the height. Both axes are periodic
+/// on their own, so the CRT combines the two into the exact second, which is then checked for
+/// the tree's frame.
+fn part2(robots: &[Robot], bounds: Point) -> Option<i32> {
+    let tx = (0..bounds.x)
+        .min_by_key(|&t| spread(robots, bounds, t, |p| p.x))
+        .expect("non-empty range");
+    let ty = (0..bounds.y)
+        .min_by_key(|&t| spread(robots, bounds, t, |p| p.y))
+        .expect("non-empty range");
+    let (seconds, _) = crt(&[(tx as i64, bounds.x as i64), (ty as i64, bounds.y as i64)])?;
+    Some(seconds as i32).filter(|&t| shows_tree(robots, bounds, t))
+}
+
+fn robots_frame(robots: &[Robot], bounds: Point, seconds: i32) -> Frame {
+    let positions = robots
+        .iter()
+        .map(|r| move_robot(r, bounds, seconds))
+        .collect_vec();
+    let mut frame = Frame::from_fn(bounds.x as usize, bounds.y as usize, |_| '.');
+    frame.agents(&positions, '0');
+    frame
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day14.txt").expect("file read err");
-    let robots = parse_input(&input);
+    let input = Input::read("inputs/day14.txt");
+    let robots = parse_input(&input).unwrap_or_else(|e| panic!("Error reading input: {e}"));
     println!("Part 1: {}", part1(&robots, BOUNDS, 100));
-    let easter_egg_seconds = part2(&robots, BOUNDS);
+    let Some(easter_egg_seconds) = part2(&robots, BOUNDS) else {
+        println!("Part 2: no tree found");
+        return;
+    };
     println!("Part 2: {}", easter_egg_seconds);
-    match env::var("DEBUG") {
-        Ok(pretty_print) if ["true", "t", "1"].contains(&pretty_print.as_str()) => {
-            println!(
-                "{}",
-                flat_grid_from_robots(
-                    &robots
-                        .iter()
-                        .map(|r| Robot {
-                            point: move_robot(r, BOUNDS, easter_egg_seconds),
-                            vector: r.vector
-                        })
-                        .collect_vec(),
-                    BOUNDS.x as usize,
-                    BOUNDS.y as usize
-                )
-            );
+    // lead up to the tree so it's clear it wasn't there a moment ago
+    let lead_up = (easter_egg_seconds - 5).max(0)..=easter_egg_seconds;
+    if let Some(mut renderer) = render::renderer() {
+        for t in lead_up.clone() {
+            renderer
+                .draw(&robots_frame(&robots, BOUNDS, t), &format!("{t} seconds"))
+                .expect("write frame");
         }
-        _ => {}
+    }
+    if let Some(mut frames) = image::exporter("day14", ImageExport::new(Format::Pgm)) {
+        let path = lead_up
+            .map(|t| {
+                frames
+                    .write(&robots_frame(&robots, BOUNDS, t))
+                    .expect("write image")
+            })
+            .last()
+            .expect("non-empty range");
+        println!("Tree written to {}", path.display());
     }
 }
 
@@ -187,8 +198,10 @@ p=9,5 v=-3,-3";
 
     #[test]
     fn test_parse() {
-        let robots = parse_input(TEST_INPUT);
+        let robots = parse_input(TEST_INPUT).unwrap();
         assert_eq!(robots.len(), 12);
+        let err = parse_input("p=0,4 v=3,-3\np=6,3 v=-1").unwrap_err();
+        assert_eq!(err.line, 2);
         assert_eq!(
             robots[0],
             Robot {
@@ -215,8 +228,41 @@ p=9,5 v=-3,-3";
 
     #[test]
     fn test_part1() {
-        let robots = parse_input(TEST_INPUT);
+        let robots = parse_input(TEST_INPUT).unwrap();
         let product = part1(&robots, (11, 7).into(), 100);
         println!("product: {product}");
     }
+
+    #[test]
+    fn test_part2() {
+        // a 20x12 block of robots (every row a run of 20) that only comes together at `tree`,
+        // hidden among robots that never line up
+        let tree = 4321;
+        let mut seed = 14u64;
+        let mut rand = |n: i32| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((seed >> 33) % n as u64) as i32
+        };
+        let mut robots = vec![];
+        for (x, y) in (40..60).cartesian_product(50..62) {
+            let vector = Point::new(rand(201) - 100, rand(207) - 103);
+            let point = Point::new(x - vector.x * tree, y - vector.y * tree);
+            robots.push(Robot {
+                point: move_robot(&Robot { point, vector }, BOUNDS, 0),
+                vector,
+            });
+        }
+        for _ in 0..300 {
+            let point = Point::new(rand(BOUNDS.x), rand(BOUNDS.y));
+            let vector = Point::new(rand(201) - 100, rand(207) - 103);
+            robots.push(Robot { point, vector });
+        }
+        assert!(shows_tree(&robots, BOUNDS, tree));
+        assert!(!shows_tree(&robots, BOUNDS, tree + 1));
+        assert_eq!(part2(&robots, BOUNDS), Some(tree));
+        // without the block there is nothing to find
+        assert_eq!(part2(&robots[240..], BOUNDS), None);
+    }
 }
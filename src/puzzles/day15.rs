@@ -1,38 +1,60 @@
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
 
 use simple_grid::{Grid, GridIndex};
 
-use crate::shared::grid2d::Direction;
+use crate::shared::{
+    grid2d::Direction,
+    parse::sections,
+    point::Point,
+    render::{self, Frame},
+    util::Input,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Tile {
     Empty,
     Player,
     Box,
+    /// Left half of a wide `[]` box
+    BoxLeft,
+    /// Right half of a wide `[]` box
+    BoxRight,
     Wall,
 }
 
+impl From<char> for Tile {
+    fn from(value: char) -> Self {
+        match value {
+            '.' => Tile::Empty,
+            '@' => Tile::Player,
+            'O' => Tile::Box,
+            '[' => Tile::BoxLeft,
+            ']' => Tile::BoxRight,
+            '#' => Tile::Wall,
+            _ => panic!("invalid char"),
+        }
+    }
+}
+
+impl From<Tile> for char {
+    fn from(tile: Tile) -> Self {
+        match tile {
+            Tile::Empty => '.',
+            Tile::Player => '@',
+            Tile::Box => 'O',
+            Tile::BoxLeft => '[',
+            Tile::BoxRight => ']',
+            Tile::Wall => '#',
+        }
+    }
+}
+
 fn parse_input(input: &str) -> (Grid<Tile>, VecDeque<Direction>) {
-    let split = input.split("\n\n").collect::<Vec<_>>();
-    let (map, moves) = (split[0], split[1]);
-    let width = map.lines().next().unwrap().chars().count();
-    let height = map.lines().count();
+    let [map, moves] = sections(input)[..] else {
+        panic!("expected a map and moves separated by a blank line");
+    };
     (
-        Grid::new(
-            width,
-            height,
-            map.lines()
-                .flat_map(|line| {
-                    line.chars().map(|c| match c {
-                        '.' => Tile::Empty,
-                        '@' => Tile::Player,
-                        'O' => Tile::Box,
-                        '#' => Tile::Wall,
-                        _ => panic!("invalid char"),
-                    })
-                })
-                .collect(),
-        ),
+        Input::from(map.text).grid_with(Tile::from),
         moves
             .lines()
             .flat_map(|line| {
@@ -48,6 +70,19 @@ fn parse_input(input: &str) -> (Grid<Tile>, VecDeque<Direction>) {
     )
 }
 
+/// Part 2's warehouse: everything but the player is twice as wide
+fn widen(grid: &Grid<Tile>) -> Grid<Tile> {
+    let cells = grid
+        .cell_iter()
+        .flat_map(|&tile| match tile {
+            Tile::Box => [Tile::BoxLeft, Tile::BoxRight],
+            Tile::Player => [Tile::Player, Tile::Empty],
+            other => [other, other],
+        })
+        .collect();
+    Grid::new(grid.width() * 2, grid.height(), cells)
+}
+
 fn find_player(grid: &Grid<Tile>) -> GridIndex {
     grid.cells_with_indices_iter()
         .find(|(_, &cell)| cell == Tile::Player)
@@ -55,29 +90,85 @@ fn find_player(grid: &Grid<Tile>) -> GridIndex {
         .0
 }
 
-fn tick(grid: &mut Grid<Tile>, cur_move: Direction) {
-    let player_pos = find_player(&grid);
-    let cur_tile = Some(player_pos);
-    let mut cur_stack = Vec::with_capacity(grid.width() * grid.height());
-    cur_stack.push(player_pos);
-    match cur_move {
-        Direction::Up => {}
-        Direction::Right => {}
-        Direction::Down => {}
-        Direction::Left => {
-            let row: Vec<Tile> = grid
-                .row_iter(player_pos.row())
-                .enumerate()
-                .filter_map(|(x, &tile)| (x <= player_pos.column()).then_some(tile))
-                .collect();
-            log::debug!("{row:?}");
+fn index(pt: Point) -> GridIndex {
+    pt.try_into().expect("walls keep everything on the map")
+}
+
+fn tile_at(grid: &Grid<Tile>, pt: Point) -> Tile {
+    *grid
+        .get(index(pt))
+        .expect("walls keep everything on the map")
+}
+
+/// Try to move the player at `player` one step in `dir`, pushing whatever is in the way. A
+/// push spreads out like a tree: every cell that moves needs the cell ahead of it free or moving
+/// too, and pushing half of a wide box up or down drags the other half along. If anything in
+/// the tree hits a wall nothing moves. Returns where the player ends up.
+fn tick(grid: &mut Grid<Tile>, player: Point, dir: Direction) -> Point {
+    let mut moving = vec![player];
+    let mut seen = HashSet::from([player]);
+    let mut queue = VecDeque::from([player]);
+    while let Some(pos) = queue.pop_front() {
+        let ahead = pos.step(dir);
+        let mut pushed = match tile_at(grid, ahead) {
+            Tile::Wall => return player,
+            Tile::Empty => vec![],
+            Tile::Box | Tile::Player => vec![ahead],
+            Tile::BoxLeft => vec![ahead, ahead.step(Direction::Right)],
+            Tile::BoxRight => vec![ahead, ahead.step(Direction::Left)],
+        };
+        // sideways pushes reach the other half on their own
+        if matches!(dir, Direction::Left | Direction::Right) {
+            pushed.truncate(1);
+        }
+        for next in pushed {
+            if seen.insert(next) {
+                moving.push(next);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    let tiles = moving
+        .iter()
+        .map(|&pt| (pt, grid.replace_cell(index(pt), Tile::Empty)))
+        .collect::<Vec<_>>();
+    for (pt, tile) in tiles {
+        grid.replace_cell(index(pt.step(dir)), tile);
+    }
+    player.step(dir)
+}
+
+/// Sum of `100 * row + column` over every box, measured to the left edge of wide boxes
+fn gps_sum(grid: &Grid<Tile>) -> usize {
+    grid.cells_with_indices_iter()
+        .filter(|(_, &tile)| matches!(tile, Tile::Box | Tile::BoxLeft))
+        .map(|(idx, _)| 100 * idx.row() + idx.column())
+        .sum()
+}
+
+/// Play every move and return the GPS sum of the final warehouse
+fn run(mut grid: Grid<Tile>, moves: &VecDeque<Direction>) -> usize {
+    let mut renderer = render::renderer();
+    let mut player = Point::from(find_player(&grid));
+    for (count, &dir) in moves.iter().enumerate() {
+        player = tick(&mut grid, player, dir);
+        if let Some(renderer) = renderer.as_mut().filter(|r| !r.is_closed()) {
+            let mut frame = Frame::from_grid(&grid, |&t| t.into());
+            frame.agents(&[player], '@');
+            renderer
+                .draw(&frame, &format!("move {} of {}", count + 1, moves.len()))
+                .expect("write frame");
         }
     }
+    gps_sum(&grid)
 }
 
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day15.txt");
+    let (grid, moves) = parse_input(&input);
+    println!("Part 1: {}", run(grid.clone(), &moves));
+    println!("Part 2: {}", run(widen(&grid), &moves));
 }
 
 #[cfg(test)]
@@ -116,16 +207,59 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
         assert_eq!(moves[3], Direction::Right);
     }
 
+    const SMALL_INPUT: &str = "\
+########
+#..O.O.#
+##@.O..#
+#...O..#
+#.#.O..#
+#...O..#
+#......#
+########
+
+<^^>>>vv<v>>v<<";
+
     #[test]
     fn test_part1() {
         test_setup();
-        let (mut grid, mut moves) = parse_input(TEST_INPUT);
-        tick(&mut grid, moves.pop_front().unwrap());
+        let (grid, moves) = parse_input(SMALL_INPUT);
+        assert_eq!(run(grid, &moves), 2028);
+        let (grid, moves) = parse_input(TEST_INPUT);
+        assert_eq!(run(grid, &moves), 10092);
     }
 
     #[test]
-    #[ignore = "wip"]
     fn test_part2() {
         test_setup();
+        let (grid, moves) = parse_input(TEST_INPUT);
+        let wide = widen(&grid);
+        assert_eq!(find_player(&wide), (8, 4).into());
+        assert_eq!(run(wide, &moves), 9021);
+    }
+
+    #[test]
+    fn test_wide_tree_push() {
+        test_setup();
+        let map = "\
+##############
+##......##..##
+##..........##
+##...[][]...##
+##....[]....##
+##.....@....##
+##############
+
+^";
+        let (mut grid, _) = parse_input(map);
+        let start = find_player(&grid).into();
+        let player = tick(&mut grid, start, Direction::Up);
+        assert_eq!(player, Point::new(7, 4));
+        let row =
+            |grid: &Grid<Tile>, r| grid.row_iter(r).map(|&t| char::from(t)).collect::<String>();
+        assert_eq!(row(&grid, 2), "##...[][]...##");
+        assert_eq!(row(&grid, 3), "##....[]....##");
+        // the right box of the pair is under a wall now, so the whole tree stays put
+        assert_eq!(tick(&mut grid, player, Direction::Up), player);
+        assert_eq!(row(&grid, 2), "##...[][]...##");
     }
 }
@@ -1,93 +1,119 @@
-use core::panic;
+use std::collections::HashSet;
 
-use crate::shared::graph_util::get_nbr_indices;
-use itertools::Itertools;
-use petgraph::{algo::astar, graph::NodeIndex, Graph, Undirected};
+use simple_grid::Grid;
+
+use crate::shared::{
+    grid2d::Direction,
+    image::{self, Format, ImageExport},
+    lattice::Bounds,
+    point::Point,
+    render::{self, Frame},
+    search::{dijkstra, CostModel, Pose, ShortestPaths},
+    util::Input,
+};
 
 #[derive(Debug)]
 struct Maze {
-    start: NodeIndex,
-    end: NodeIndex,
-    width: usize,
-    height: usize,
-    graph: Graph<char, u32, Undirected>,
+    start: Point,
+    end: Point,
+    tiles: Grid<char>,
 }
 
 impl Maze {
-    fn display_path(&self, path: &[NodeIndex]) {
-        println!(
-            "{}",
-            (0u32..self.height as u32)
-                .map(|y| {
-                    (0u32..self.width as u32)
-                        .flat_map(|x| {
-                            let node_idx = NodeIndex::from(y * self.width as u32 + x);
-                            if path.contains(&node_idx) {
-                                Some('o')
-                            } else {
-                                Some(self.graph[node_idx])
-                            }
-                        })
-                        .collect::<String>()
-                })
-                .join("\n")
-        )
+    fn is_open(&self, pt: Point) -> bool {
+        self.tiles.contains_point(pt)
+            && self.tiles.get((pt.x as usize, pt.y as usize)) == Some(&'.')
     }
-}
-
-fn parse_input(input: &str) -> Maze {
-    let width = input.lines().peekable().peek().unwrap().chars().count();
-    let height = input.lines().count();
-    let mut graph: Graph<char, u32, Undirected> = Graph::new_undirected();
-    let mut flat_vec = input.lines().flat_map(|line| line.chars()).collect_vec();
-    let start = match flat_vec.iter().position(|&c| c == 'S') {
-        Some(pos) => NodeIndex::from(pos as u32),
-        None => panic!("no start"),
-    };
-    let end = match flat_vec.iter().position(|&c| c == 'E') {
-        Some(pos) => NodeIndex::from(pos as u32),
-        None => panic!("no end"),
-    };
-    flat_vec[start.index()] = '.';
-    flat_vec[end.index()] = '.';
 
-    for c in &flat_vec {
-        graph.add_node(*c);
+    /// The maze with every tile in `path` drawn as `O`
+    fn display_path<'a, I>(&self, path: I) -> Frame
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        let mut frame = Frame::from_grid(&self.tiles, |&c| c);
+        frame
+            .path(path)
+            .agents([&self.start], 'S')
+            .agents([&self.end], 'E');
+        frame
     }
-    for idx in (0u32..flat_vec.len() as u32).map(NodeIndex::from) {
-        let cur_node = graph[idx];
-        for (cost, nbr) in get_nbr_indices(idx, width, height) {
-            match (cur_node, graph[nbr]) {
-                ('#', '.') | ('.', '#') | ('#', '#') => {}
-                ('.', '.') => {
-                    graph.add_edge(idx, nbr, cost);
-                }
-                _ => panic!("invalid characters: {cur_node}, {}", graph[nbr]),
-            };
-        }
+}
+
+/// A step forward costs 1 point and a quarter turn costs 1000
+impl CostModel<u32> for Maze {
+    fn forward(&self, pose: Pose) -> Option<u32> {
+        self.is_open(pose.pos.step(pose.facing)).then_some(1)
     }
-    Maze {
-        start,
-        end,
-        width,
-        height,
-        graph,
+
+    fn turn(&self, _: Pose, _: Direction) -> Option<u32> {
+        Some(1000)
     }
 }
 
-fn part1_astar(maze: &Maze) -> Option<(u32, Vec<NodeIndex>)> {
-    astar(
-        &maze.graph,
-        maze.start,
-        |f| f == maze.end,
-        |e| *e.weight(),
-        |_| (maze.width * maze.height) as u32,
-    )
+fn parse_input(input: &Input) -> Maze {
+    let mut tiles = input.grid();
+    let mut find = |target: char| {
+        let idx = tiles
+            .position(|&c| c == target)
+            .unwrap_or_else(|| panic!("no {target} in maze"));
+        tiles.replace_cell(idx, '.');
+        Point::from(idx)
+    };
+    let (start, end) = (find('S'), find('E'));
+    Maze { start, end, tiles }
+}
+
+/// Every reachable pose from S facing east, with all of the cheapest ways to get there
+fn search(maze: &Maze) -> ShortestPaths<Pose, u32> {
+    dijkstra([Pose::new(maze.start, Direction::Right)], |pose| {
+        pose.successors(maze)
+    })
+}
+
+/// Lowest score to reach E, the poses E is reached in at that score, and every tile on any path
+/// that scores it. E can be reached facing any direction, so all the facings that tie for the
+/// best score count.
+fn best_paths(
+    maze: &Maze,
+    paths: &ShortestPaths<Pose, u32>,
+) -> Option<(u32, Vec<Pose>, HashSet<Point>)> {
+    let (score, ends) = paths.best_ends(Direction::ALL.map(|d| Pose::new(maze.end, d)))?;
+    let tiles = paths
+        .on_any_path(ends.iter().copied())
+        .into_iter()
+        .map(|p| p.pos)
+        .collect();
+    Some((score, ends, tiles))
 }
 
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day16.txt");
+    let maze = parse_input(&input);
+    let paths = search(&maze);
+    let Some((score, ends, tiles)) = best_paths(&maze, &paths) else {
+        println!("Part 1: no path");
+        return;
+    };
+    if let Some(mut renderer) = render::renderer() {
+        renderer
+            .draw(&maze.display_path(&tiles), "best seats")
+            .expect("write frame");
+    }
+    if let Some(mut frames) = image::exporter("day16", ImageExport::new(Format::Ppm)) {
+        // one cheapest route per facing E is reached in, then every best seat
+        for &end in &ends {
+            let route = paths.path_to(end).into_iter().flatten().map(|p| p.pos);
+            frames
+                .write(&maze.display_path(&route.collect::<Vec<_>>()))
+                .expect("write image");
+        }
+        let path = frames
+            .write(&maze.display_path(&tiles))
+            .expect("write image");
+        println!("Paths written up to {}", path.display());
+    }
+    println!("Part 1: {score}");
+    println!("Part 2: {}", tiles.len());
 }
 
 #[cfg(test)]
@@ -134,27 +160,31 @@ mod test {
     #[test]
     fn test_parse() {
         test_setup();
-        let maze = parse_input(TEST_INPUT);
-        assert_eq!(maze.height, 15);
-        assert_eq!(maze.width, 15);
+        let maze = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(maze.tiles.height(), 15);
+        assert_eq!(maze.tiles.width(), 15);
+        assert_eq!(maze.start, Point::new(1, 13));
+        assert_eq!(maze.end, Point::new(13, 1));
     }
 
     #[test]
     fn test_part1() {
         test_setup();
-        let maze = parse_input(TEST_INPUT);
-        let (dist, path) = part1_astar(&maze).unwrap();
-        println!("dist: {dist}, path: {path:?}");
-        maze.display_path(&path);
-        let maze2 = parse_input(TEST_INPUT);
-        let (dist2, path2) = part1_astar(&maze2).unwrap();
-        maze2.display_path(&path2);
-        println!("dist: {dist2}, path: {path2:?}");
+        let maze = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(best_paths(&maze, &search(&maze)).unwrap().0, 7036);
+        let maze2 = parse_input(&Input::from(TEST_INPUT2));
+        assert_eq!(best_paths(&maze2, &search(&maze2)).unwrap().0, 11048);
     }
 
     #[test]
-    #[ignore = "wip"]
     fn test_part2() {
         test_setup();
+        let maze = parse_input(&Input::from(TEST_INPUT));
+        let (_, ends, tiles) = best_paths(&maze, &search(&maze)).unwrap();
+        log::debug!("\n{}", maze.display_path(&tiles));
+        assert_eq!(tiles.len(), 45);
+        assert_eq!(ends, vec![Pose::new(maze.end, Direction::Up)]);
+        let maze2 = parse_input(&Input::from(TEST_INPUT2));
+        assert_eq!(best_paths(&maze2, &search(&maze2)).unwrap().2.len(), 64);
     }
 }
@@ -1,6 +1,274 @@
+use std::io::{self, Write};
+
+use itertools::Itertools;
+
+use crate::shared::{
+    parse::{self, ints, ParseError},
+    util::{self, Input},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Adv,
+    Bxl,
+    Bst,
+    Jnz,
+    Bxc,
+    Out,
+    Bdv,
+    Cdv,
+}
+
+impl From<u8> for Op {
+    fn from(code: u8) -> Self {
+        match code {
+            0 => Op::Adv,
+            1 => Op::Bxl,
+            2 => Op::Bst,
+            3 => Op::Jnz,
+            4 => Op::Bxc,
+            5 => Op::Out,
+            6 => Op::Bdv,
+            7 => Op::Cdv,
+            _ => panic!("invalid opcode {code}"),
+        }
+    }
+}
+
+impl Op {
+    fn uses_combo(self) -> bool {
+        matches!(self, Op::Adv | Op::Bst | Op::Out | Op::Bdv | Op::Cdv)
+    }
+}
+
+/// Combo operands 0-3 are literals and 4-6 name a register
+fn combo_name(operand: u8) -> String {
+    match operand {
+        0..=3 => operand.to_string(),
+        4 => "A".to_owned(),
+        5 => "B".to_owned(),
+        6 => "C".to_owned(),
+        _ => panic!("reserved combo operand {operand}"),
+    }
+}
+
+/// One instruction in readable form, e.g. `adv 3    A = A >> 3`
+fn disassemble_one(op: Op, operand: u8) -> String {
+    let arg = if op.uses_combo() {
+        combo_name(operand)
+    } else {
+        operand.to_string()
+    };
+    let effect = match op {
+        Op::Adv => format!("A = A >> {arg}"),
+        Op::Bxl => format!("B = B ^ {arg}"),
+        Op::Bst => format!("B = {arg} & 7"),
+        Op::Jnz => format!("if A != 0 jump {arg}"),
+        Op::Bxc => "B = B ^ C".to_owned(),
+        Op::Out => format!("out {arg} & 7"),
+        Op::Bdv => format!("B = A >> {arg}"),
+        Op::Cdv => format!("C = A >> {arg}"),
+    };
+    let name = format!("{op:?}").to_lowercase();
+    format!("{name} {arg:<4} {effect}")
+}
+
+/// Every instruction with its address
+fn disassemble(program: &[u8]) -> Vec<String> {
+    program
+        .chunks(2)
+        .enumerate()
+        .map(|(i, pair)| match *pair {
+            [op, operand] => format!("{:02}: {}", 2 * i, disassemble_one(op.into(), operand)),
+            [op] => format!("{:02}: {:?} (no operand)", 2 * i, Op::from(op)),
+            _ => unreachable!(),
+        })
+        .collect()
+}
+
+/// Programs that haven't halted after this many instructions are assumed to loop forever
+const STEP_LIMIT: usize = 1 << 20;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Computer {
+    a: u64,
+    b: u64,
+    c: u64,
+    ip: usize,
+    program: Vec<u8>,
+    output: Vec<u8>,
+    steps: usize,
+}
+
+impl Computer {
+    fn new(registers: [u64; 3], program: Vec<u8>) -> Self {
+        let [a, b, c] = registers;
+        Self {
+            a,
+            b,
+            c,
+            ip: 0,
+            program,
+            output: vec![],
+            steps: 0,
+        }
+    }
+
+    fn combo(&self, operand: u8) -> u64 {
+        match operand {
+            0..=3 => operand as u64,
+            4 => self.a,
+            5 => self.b,
+            6 => self.c,
+            _ => panic!("reserved combo operand {operand}"),
+        }
+    }
+
+    /// `A >> combo`, where shifting by 64 or more bits leaves nothing (A / 2^n rounds to 0)
+    fn a_shifted(&self, operand: u8) -> u64 {
+        let n = u32::try_from(self.combo(operand)).unwrap_or(u32::MAX);
+        self.a.checked_shr(n).unwrap_or(0)
+    }
+
+    /// Execute the instruction at `ip`. Returns `false` once the program has halted.
+    fn step(&mut self) -> bool {
+        let (Some(&code), Some(&operand)) =
+            (self.program.get(self.ip), self.program.get(self.ip + 1))
+        else {
+            return false;
+        };
+        self.ip += 2;
+        self.steps += 1;
+        match Op::from(code) {
+            Op::Adv => self.a = self.a_shifted(operand),
+            Op::Bxl => self.b ^= operand as u64,
+            Op::Bst => self.b = self.combo(operand) & 7,
+            Op::Jnz => {
+                if self.a != 0 {
+                    self.ip = operand as usize;
+                }
+            }
+            Op::Bxc => self.b ^= self.c,
+            Op::Out => self.output.push((self.combo(operand) & 7) as u8),
+            Op::Bdv => self.b = self.a_shifted(operand),
+            Op::Cdv => self.c = self.a_shifted(operand),
+        }
+        true
+    }
+
+    /// Run until the program halts and return its output, or `None` if it's still going after
+    /// [`STEP_LIMIT`] instructions
+    fn run(&mut self) -> Option<&[u8]> {
+        while self.step() {
+            if self.steps >= STEP_LIMIT {
+                return None;
+            }
+        }
+        Some(&self.output)
+    }
+
+    /// Like [`Computer::run`], writing each instruction and the registers after it to `out`.
+    /// Returns whether the program halted.
+    fn trace<W: Write>(&mut self, out: &mut W) -> io::Result<bool> {
+        loop {
+            let ip = self.ip;
+            let Some(&[code, operand]) = self.program.get(ip..ip + 2) else {
+                writeln!(out, "halt  output: {}", self.output_string())?;
+                return Ok(true);
+            };
+            if self.steps >= STEP_LIMIT {
+                writeln!(out, "no halt after {STEP_LIMIT} steps")?;
+                return Ok(false);
+            }
+            self.step();
+            writeln!(
+                out,
+                "{ip:02}: {:<28} A={:<#18o} B={:<#18o} C={:#o}",
+                disassemble_one(code.into(), operand),
+                self.a,
+                self.b,
+                self.c
+            )?;
+        }
+    }
+
+    fn output_string(&self) -> String {
+        self.output.iter().join(",")
+    }
+}
+
+fn parse_input(input: &str) -> parse::Result<([u64; 3], Vec<u8>)> {
+    let numbers = ints::<u64>(input)?;
+    let [a, b, c, ..] = numbers[..] else {
+        return Err(ParseError {
+            line: 1,
+            text: input.lines().next().unwrap_or_default().to_owned(),
+            message: "expected three registers and a program".to_owned(),
+        });
+    };
+    let program = numbers[3..]
+        .iter()
+        .map(|&n| match u8::try_from(n) {
+            Ok(n @ 0..=7) => Ok(n),
+            _ => {
+                let (idx, line) = input
+                    .lines()
+                    .enumerate()
+                    .find(|(_, line)| line.starts_with("Program"))
+                    .unwrap_or((0, ""));
+                Err(ParseError {
+                    line: idx + 1,
+                    text: line.to_owned(),
+                    message: format!("{n} is not a 3-bit number"),
+                })
+            }
+        })
+        .collect::<parse::Result<_>>()?;
+    Ok(([a, b, c], program))
+}
+
+/// The program is a loop that prints something derived from the low bits of A, then drops the
+/// lowest octal digit of A (`adv 3`) until A is zero. So the last output only depends on the
+/// highest octal digit of A, the last two outputs on the top two digits, and so on. Build A
+/// one octal digit at a time from the top, keeping every candidate that reproduces the tail of
+/// the program so far.
+fn find_quine(registers: [u64; 3], program: &[u8]) -> Option<u64> {
+    let [_, b, c] = registers;
+    let mut candidates = vec![0u64];
+    for start in (0..program.len()).rev() {
+        candidates = candidates
+            .into_iter()
+            .flat_map(|prefix| (0..8).map(move |digit| prefix * 8 + digit))
+            .filter(|&a| {
+                Computer::new([a, b, c], program.to_vec()).run() == Some(&program[start..])
+            })
+            .collect();
+    }
+    candidates.into_iter().filter(|&a| a > 0).min()
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day17.txt");
+    let (registers, program) =
+        parse_input(&input).unwrap_or_else(|e| panic!("Error reading input: {e}"));
+    let mut computer = Computer::new(registers, program.clone());
+    let halted = if util::debug() {
+        println!("{}", disassemble(&program).join("\n"));
+        computer
+            .trace(&mut io::stdout())
+            .expect("error writing trace")
+    } else {
+        computer.run().is_some()
+    };
+    if halted {
+        println!("Part 1: {}", computer.output_string());
+    } else {
+        println!("Part 1: no halt after {STEP_LIMIT} steps");
+    }
+    match find_quine(registers, &program) {
+        Some(a) => println!("Part 2: {a}"),
+        None => println!("Part 2: no quine"),
+    }
 }
 
 #[cfg(test)]
@@ -8,16 +276,75 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+Register A: 729
+Register B: 0
+Register C: 0
+
+Program: 0,1,5,4,3,0";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let (registers, program) = parse_input(TEST_INPUT).unwrap();
+        assert_eq!(registers, [729, 0, 0]);
+        assert_eq!(program, vec![0, 1, 5, 4, 3, 0]);
+        assert_eq!(disassemble(&program)[1], "02: out A    out A & 7");
+        // a register too big for u64 must not be misread as the start of the program
+        let err = parse_input(&TEST_INPUT.replace("729", "99999999999999999999")).unwrap_err();
+        assert_eq!(err.line, 1);
+        // program values are 3-bit, so 264 must not wrap around to opcode 0
+        let err = parse_input(&TEST_INPUT.replace("0,1,5", "264,1,5")).unwrap_err();
+        assert_eq!(err.line, 5);
+        assert!(err.message.contains("264"));
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        let (registers, program) = parse_input(TEST_INPUT).unwrap();
+        let mut computer = Computer::new(registers, program);
+        computer.run();
+        assert_eq!(computer.output_string(), "4,6,3,5,6,3,5,2,1,0");
+
+        // small examples from the puzzle text
+        let mut computer = Computer::new([0, 0, 9], vec![2, 6]);
+        computer.run();
+        assert_eq!(computer.b, 1);
+        let mut computer = Computer::new([2024, 0, 0], vec![0, 1, 5, 4, 3, 0]);
+        assert_eq!(computer.run(), Some(&[4, 2, 5, 6, 7, 7, 7, 7, 3, 1, 0][..]));
+        assert_eq!(computer.a, 0);
+        let mut computer = Computer::new([0, 2024, 43690], vec![4, 0]);
+        computer.run();
+        assert_eq!(computer.b, 44354);
+
+        // shifting by a register holding 64 or more divides down to zero
+        let mut computer = Computer::new([u64::MAX, 64, 1 << 40], vec![6, 5, 7, 6, 0, 4]);
+        computer.run();
+        assert_eq!((computer.a, computer.b, computer.c), (0, 0, 0));
+
+        let mut trace = vec![];
+        Computer::new([10, 0, 0], vec![5, 0, 5, 1, 5, 4])
+            .trace(&mut trace)
+            .unwrap();
+        let trace = String::from_utf8(trace).unwrap();
+        assert!(trace.ends_with("halt  output: 0,1,2\n"));
+
+        // jnz back to the start while A stays non-zero never halts
+        let mut computer = Computer::new([1, 0, 0], vec![5, 4, 3, 0]);
+        assert_eq!(computer.run(), None);
+        assert_eq!(computer.steps, STEP_LIMIT);
+        let mut trace = vec![];
+        let halted = Computer::new([1, 0, 0], vec![3, 0])
+            .trace(&mut trace)
+            .unwrap();
+        assert!(!halted);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        let program = vec![0, 3, 5, 4, 3, 0];
+        assert_eq!(find_quine([2024, 0, 0], &program), Some(117440));
+    }
 }
@@ -1,61 +1,49 @@
-use std::fs::read_to_string;
+use std::collections::HashSet;
 
-use crate::shared::graph_util::{get_nbr_indices, get_node_idx};
-use crate::shared::util::clear_screen;
+use crate::shared::{
+    bit_grid::BitGrid,
+    parse::coords,
+    point::Point,
+    render::{self, Frame},
+    search::{bfs, ShortestPaths},
+    util::Input,
+};
 use itertools::Itertools;
-use petgraph::algo::astar;
-use petgraph::prelude::{EdgeRef, Graph, NodeIndex, Undirected};
 
 fn parse_input(input: &str) -> Vec<(u32, u32)> {
-    input
-        .lines()
-        .map(|line| {
-            let split = line.split_once(',').unwrap();
-            (
-                split.0.parse().expect("not digit"),
-                split.1.parse().expect("not digit"),
-            )
-        })
-        .collect_vec()
+    coords(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
-fn graph_from_bytes(bytes: &[(u32, u32)], width: u32, height: u32) -> Graph<u32, u32, Undirected> {
-    let mut edges = vec![];
-    for y in 0..height {
-        for x in 0..width {
-            if !bytes.contains(&(x, y)) {
-                let node_idx = y * width + x;
-                let neighbors = get_nbr_indices(node_idx, width as usize, height as usize);
-                for (_, nbr) in neighbors {
-                    let (nx, ny) = (nbr.index() as u32 % width, nbr.index() as u32 / width);
-                    if !bytes.contains(&(nx, ny)) {
-                        edges.push((node_idx, nbr.index() as u32, 1u32));
-                    }
-                }
-            }
-        }
+fn fallen(bytes: &[(u32, u32)], width: u32, height: u32) -> BitGrid {
+    let mut grid = BitGrid::new(width as usize, height as usize);
+    for &(x, y) in bytes {
+        grid.set(x as usize, y as usize);
     }
-    Graph::from_edges(&edges)
+    grid
+}
+
+/// BFS distance map from the top-left corner over every cell not yet corrupted
+fn distances(corrupted: &BitGrid, width: u32, height: u32) -> ShortestPaths<Point, usize> {
+    let max = Point::new(width as i32 - 1, height as i32 - 1);
+    bfs([Point::new(0, 0)], |pt: Point| {
+        pt.cardinal_neighbors().into_iter().filter(|nbr| {
+            nbr.in_bounds((0, 0).into(), max) && !corrupted.test_signed(nbr.x as i64, nbr.y as i64)
+        })
+    })
 }
 
-fn display_graph(
-    graph: &Graph<u32, u32, Undirected>,
-    path: &[NodeIndex],
-    bytes: &[(u32, u32)],
-    width: u32,
-    height: u32,
-) -> String {
-    (0u32..height)
+fn display_path(path: &HashSet<Point>, corrupted: &BitGrid, width: u32, height: u32) -> String {
+    (0..height as i32)
         .map(|y| {
-            (0u32..width)
-                .flat_map(|x| {
-                    let node_idx = get_node_idx(x, y, width);
-                    if path.contains(&node_idx) {
-                        Some('O')
-                    } else if bytes.contains(&(x, y)) {
-                        Some('#')
+            (0..width as i32)
+                .map(|x| {
+                    let pt = Point::new(x, y);
+                    if path.contains(&pt) {
+                        'O'
+                    } else if corrupted.test(x as usize, y as usize) {
+                        '#'
                     } else {
-                        Some('.')
+                        '.'
                     }
                 })
                 .join(" ")
@@ -63,50 +51,77 @@ fn display_graph(
         .join("\n")
 }
 
-fn remove_edges_for_xy(graph: &mut Graph<u32, u32, Undirected>, x: u32, y: u32, width: u32) {
-    let node_idx = get_node_idx(x, y, width);
-    let edges = graph.edges(node_idx).map(|eref| eref.id()).collect_vec();
-    for edge in edges {
-        _ = graph.remove_edge(edge);
+fn part1(bytes: &[(u32, u32)], width: u32, height: u32) -> Option<usize> {
+    let corrupted = fallen(bytes, width, height);
+    let end = Point::new(width as i32 - 1, height as i32 - 1);
+    let paths = distances(&corrupted, width, height);
+    log::debug!(
+        "\n{}",
+        display_path(&paths.on_any_path([end]), &corrupted, width, height)
+    );
+    paths.cost(&end)
+}
+
+/// Binary search over how many bytes have fallen, instead of re-running a search per byte. Returns
+/// the first byte after which the exit can no longer be reached.
+fn first_blocking_byte(bytes: &[(u32, u32)], width: u32, height: u32) -> Option<(u32, u32)> {
+    let end = Point::new(width as i32 - 1, height as i32 - 1);
+    let mut renderer = render::renderer();
+    let mut reachable = |count: usize| {
+        let corrupted = fallen(&bytes[..count], width, height);
+        let paths = distances(&corrupted, width, height);
+        if let Some(renderer) = renderer.as_mut().filter(|r| !r.is_closed()) {
+            let newest = count
+                .checked_sub(1)
+                .map(|i| Point::new(bytes[i].0 as i32, bytes[i].1 as i32));
+            let mut frame = Frame::from_fn(width as usize, height as usize, |pt| {
+                if corrupted.test(pt.x as usize, pt.y as usize) {
+                    '#'
+                } else {
+                    '.'
+                }
+            });
+            frame
+                .path(&paths.on_any_path([end]))
+                .agents(newest.iter(), '#');
+            renderer
+                .draw(&frame, &format!("{count} bytes fallen"))
+                .expect("write frame");
+        }
+        paths.contains(&end)
+    };
+    let (mut lo, mut hi) = (0, bytes.len());
+    if reachable(hi) {
+        return None;
     }
+    // invariant: reachable(lo) && !reachable(hi)
+    while hi - lo > 1 {
+        let mid = (lo + hi) / 2;
+        if reachable(mid) {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    Some(bytes[hi - 1])
 }
 
 pub fn solve() {
-    let input = read_to_string("inputs/day18.txt").expect("file read error");
+    let input = Input::read("inputs/day18.txt");
     let bytes = parse_input(&input);
-    println!("bytes_len: {}", bytes.len());
     let (width, height) = (71, 71);
-    let (start, end): (NodeIndex, NodeIndex) = (0u32.into(), ((width * height) - 1).into());
-    let mut graph = graph_from_bytes(&bytes[..1024], width, height);
-    let (pt1_cost, pt1_path) = astar(&graph, start, |f| f == end, |e| *e.weight(), |_| 0).unwrap();
-    log::debug!(
-        "{}",
-        display_graph(&graph, &pt1_path, &bytes, width, height)
-    );
-    println!("Part 1: {}", pt1_cost);
-    // Part 2
-    for (cur_byte, &(x, y)) in bytes[1025..].iter().enumerate() {
-        remove_edges_for_xy(&mut graph, x, y, width);
-        if let Some((_, path)) = astar(&graph, start, |f| f == end, |e| *e.weight(), |_| 0) {
-            if log::log_enabled!(log::Level::Debug) {
-                clear_screen();
-                log::debug!(
-                    "{}",
-                    display_graph(&graph, &path, &bytes[..cur_byte + 1025], width, height)
-                );
-            }
-            continue;
-        } else {
-            println!("Part 2: ({x}, {y})",);
-            break;
-        }
+    match part1(&bytes[..1024], width, height) {
+        Some(cost) => println!("Part 1: {cost}"),
+        None => println!("Part 1: no path"),
+    }
+    match first_blocking_byte(&bytes, width, height) {
+        Some((x, y)) => println!("Part 2: {x},{y}"),
+        None => println!("Part 2: never blocked"),
     }
 }
 
 #[cfg(test)]
 mod test {
-    use petgraph::adj::NodeIndex;
-
     use super::*;
     use crate::shared::util::test_setup;
 
@@ -123,18 +138,13 @@ mod test {
     fn test_pt1() {
         test_setup();
         let bytes = parse_input(TEST_INPUT);
-        let (width, height) = (7, 7);
-        let graph = graph_from_bytes(&bytes[..12], width, height);
-        println!("{graph:?}");
-        let (cost, path) = astar(
-            &graph,
-            NodeIndex::from(0u32),
-            |f| f == NodeIndex::from(48),
-            |e| *e.weight(),
-            |_| 0,
-        )
-        .unwrap();
-        log::debug!("\n{}", display_graph(&graph, &path, &bytes, width, height));
-        assert_eq!(cost, 22);
+        assert_eq!(part1(&bytes[..12], 7, 7), Some(22));
+    }
+
+    #[test]
+    fn test_pt2() {
+        test_setup();
+        let bytes = parse_input(TEST_INPUT);
+        assert_eq!(first_blocking_byte(&bytes, 7, 7), Some((6, 1)));
     }
 }
@@ -1,6 +1,111 @@
+use std::collections::HashMap;
+
+use crate::shared::util::Input;
+
+/// Prefix tree over the towel patterns, so all patterns that start a design are found in one
+/// walk down the tree instead of trying every pattern
+#[derive(Debug, Default)]
+struct Trie {
+    nodes: Vec<Node>,
+}
+
+#[derive(Debug, Default)]
+struct Node {
+    children: HashMap<u8, usize>,
+    terminal: bool,
+}
+
+impl Trie {
+    fn new<'a, I: IntoIterator<Item = &'a str>>(patterns: I) -> Self {
+        let mut trie = Trie {
+            nodes: vec![Node::default()],
+        };
+        for pattern in patterns {
+            trie.insert(pattern.as_bytes());
+        }
+        trie
+    }
+
+    fn insert(&mut self, pattern: &[u8]) {
+        let mut cur = 0;
+        for &b in pattern {
+            cur = match self.nodes[cur].children.get(&b) {
+                Some(&next) => next,
+                None => {
+                    self.nodes.push(Node::default());
+                    let next = self.nodes.len() - 1;
+                    self.nodes[cur].children.insert(b, next);
+                    next
+                }
+            };
+        }
+        self.nodes[cur].terminal = true;
+    }
+
+    /// Lengths of every pattern that is a prefix of `s`
+    fn prefix_lens<'a>(&'a self, s: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        s.iter()
+            .scan(0, |cur, b| {
+                *cur = *self.nodes[*cur].children.get(b)?;
+                Some(*cur)
+            })
+            .enumerate()
+            .filter(|&(_, node)| self.nodes[node].terminal)
+            .map(|(i, _)| i + 1)
+    }
+}
+
+fn parse_input(input: &Input) -> (Vec<&str>, Vec<&str>) {
+    let [patterns, designs] = input.sections()[..] else {
+        panic!("expected patterns and designs separated by a blank line");
+    };
+    (
+        patterns.text.split(',').map(str::trim).collect(),
+        designs.lines().collect(),
+    )
+}
+
+/// Number of ways to build `design` from the patterns. `ways[i]` counts arrangements of the
+/// suffix starting at `i`, filled from the back so every suffix is only solved once.
+/// `None` if the count doesn't fit in a `u128`.
+fn arrangements(trie: &Trie, design: &str) -> Option<u128> {
+    let design = design.as_bytes();
+    let mut ways = vec![0u128; design.len() + 1];
+    ways[design.len()] = 1;
+    for start in (0..design.len()).rev() {
+        ways[start] = trie
+            .prefix_lens(&design[start..])
+            .try_fold(0u128, |sum, len| sum.checked_add(ways[start + len]))?;
+    }
+    Some(ways[0])
+}
+
+/// How many designs are possible at all, and the total number of arrangements (`None` if it
+/// overflows)
+fn count_designs(patterns: &[&str], designs: &[&str]) -> (usize, Option<u128>) {
+    let trie = Trie::new(patterns.iter().copied());
+    designs
+        .iter()
+        .map(|design| arrangements(&trie, design))
+        .fold((0, Some(0)), |(possible, total), ways| {
+            (
+                possible + (ways != Some(0)) as usize,
+                total
+                    .zip(ways)
+                    .and_then(|(total, ways)| total.checked_add(ways)),
+            )
+        })
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day19.txt");
+    let (patterns, designs) = parse_input(&input);
+    let (possible, total) = count_designs(&patterns, &designs);
+    println!("Part 1: {possible}");
+    match total {
+        Some(total) => println!("Part 2: {total}"),
+        None => println!("Part 2: too many arrangements to count in a u128"),
+    }
 }
 
 #[cfg(test)]
@@ -8,16 +113,51 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+r, wr, b, g, bwu, rb, gb, br
+
+brwrr
+bggr
+gbbr
+rrbgbr
+ubwu
+bwurrg
+brgr
+bbrgwb";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let input = Input::from(TEST_INPUT);
+        let (patterns, designs) = parse_input(&input);
+        assert_eq!(patterns.len(), 8);
+        assert_eq!(patterns[4], "bwu");
+        assert_eq!(designs.len(), 8);
+        let trie = Trie::new(patterns);
+        assert_eq!(trie.prefix_lens(b"bwurrg").collect::<Vec<_>>(), vec![1, 3]);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        let input = Input::from(TEST_INPUT);
+        let (patterns, designs) = parse_input(&input);
+        assert_eq!(count_designs(&patterns, &designs).0, 6);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        let input = Input::from(TEST_INPUT);
+        let (patterns, designs) = parse_input(&input);
+        assert_eq!(count_designs(&patterns, &designs).1, Some(16));
+        // long generated design: the count grows like Fibonacci, not the work
+        let long = "r".repeat(80);
+        let trie = Trie::new(["r", "rr"]);
+        assert_eq!(arrangements(&trie, &long), Some(37_889_062_373_143_906));
+        // past fib(186) the count no longer fits, which is reported instead of wrapping
+        let longer = "r".repeat(200);
+        assert_eq!(arrangements(&trie, &longer), None);
+        assert_eq!(count_designs(&["r", "rr"], &[&long, &longer]), (2, None));
+    }
 }
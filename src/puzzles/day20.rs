@@ -1,6 +1,107 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+use simple_grid::Grid;
+
+use crate::shared::{
+    counter::Counter,
+    lattice::Bounds,
+    point::Point,
+    search::bfs,
+    util::{self, Input},
+};
+
+struct Track {
+    start: Point,
+    end: Point,
+    tiles: Grid<char>,
+}
+
+impl Track {
+    fn is_open(&self, pt: Point) -> bool {
+        self.tiles.contains_point(pt)
+            && self.tiles.get((pt.x as usize, pt.y as usize)) != Some(&'#')
+    }
+
+    /// Steps along the track from `from` to every track tile
+    fn distances(&self, from: Point) -> HashMap<Point, usize> {
+        bfs([from], |pt: Point| {
+            pt.cardinal_neighbors()
+                .into_iter()
+                .filter(|&n| self.is_open(n))
+        })
+        .dist
+    }
+}
+
+fn parse_input(input: &Input) -> Track {
+    let tiles = input.grid();
+    let find = |target: char| {
+        tiles
+            .position(|&c| c == target)
+            .map(Point::from)
+            .unwrap_or_else(|| panic!("no {target} on the track"))
+    };
+    let (start, end) = (find('S'), find('E'));
+    Track { start, end, tiles }
+}
+
+/// How many cheats save each number of picoseconds. A cheat goes from track tile `a` to track
+/// tile `b` through walls in at most `max_len` steps (their manhattan distance), so the race
+/// takes `from_start[a] + |a - b| + to_end[b]` instead of the honest best time.
+fn cheat_savings(track: &Track, max_len: i32) -> Counter<usize> {
+    let from_start = track.distances(track.start);
+    let to_end = track.distances(track.end);
+    let best = from_start[&track.end];
+    let offsets = (-max_len..=max_len)
+        .cartesian_product(-max_len..=max_len)
+        .map(|(dx, dy)| Point::new(dx, dy))
+        .filter(|d| (1..=max_len).contains(&(d.x.abs() + d.y.abs())))
+        .collect_vec();
+    from_start
+        .iter()
+        .flat_map(|(&a, &to_a)| {
+            let to_end = &to_end;
+            offsets.iter().filter_map(move |&d| {
+                let from_b = to_end.get(&(a + d))?;
+                let time = to_a + (d.x.abs() + d.y.abs()) as usize + from_b;
+                best.checked_sub(time).filter(|&saved| saved > 0)
+            })
+        })
+        .collect()
+}
+
+fn count_cheats(savings: &Counter<usize>, threshold: usize) -> usize {
+    savings
+        .iter()
+        .filter(|&(&saved, _)| saved >= threshold)
+        .map(|(_, n)| n)
+        .sum()
+}
+
+/// Same wording as the puzzle's worked example, smallest savings first
+fn histogram(savings: &Counter<usize>, threshold: usize) -> Vec<String> {
+    savings
+        .iter()
+        .filter(|&(&saved, _)| saved >= threshold)
+        .sorted()
+        .map(|(saved, n)| match n {
+            1 => format!("There is one cheat that saves {saved} picoseconds."),
+            _ => format!("There are {n} cheats that save {saved} picoseconds."),
+        })
+        .collect()
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day20.txt");
+    let track = parse_input(&input);
+    for (part, max_len) in [(1, 2), (2, 20)] {
+        let savings = cheat_savings(&track, max_len);
+        if util::debug() {
+            println!("{}", histogram(&savings, 100).join("\n"));
+        }
+        println!("Part {part}: {}", count_cheats(&savings, 100));
+    }
 }
 
 #[cfg(test)]
@@ -8,16 +109,51 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+###############
+#...#...#.....#
+#.#.#.#.#.###.#
+#S#...#.#.#...#
+#######.#.#.###
+#######.#.#...#
+#######.#.###.#
+###..E#...#...#
+###.#######.###
+#...###...#...#
+#.#####.#.###.#
+#.#...#.#.#...#
+#.#.#.#.#.#.###
+#...#...#...###
+###############";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let track = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(track.start, Point::new(1, 3));
+        assert_eq!(track.end, Point::new(5, 7));
+        assert_eq!(track.distances(track.start)[&track.end], 84);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        let track = parse_input(&Input::from(TEST_INPUT));
+        let savings = cheat_savings(&track, 2);
+        let lines = histogram(&savings, 1);
+        assert_eq!(lines.len(), 11);
+        assert_eq!(lines[0], "There are 14 cheats that save 2 picoseconds.");
+        assert_eq!(lines[10], "There is one cheat that saves 64 picoseconds.");
+        assert_eq!(count_cheats(&savings, 20), 5);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        let track = parse_input(&Input::from(TEST_INPUT));
+        let savings = cheat_savings(&track, 20);
+        assert_eq!(savings.get(&50), 32);
+        assert_eq!(savings.get(&76), 3);
+        assert_eq!(count_cheats(&savings, 50), 285);
+    }
 }
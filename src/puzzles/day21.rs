@@ -1,6 +1,187 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::shared::{
+    point::Point,
+    util::{self, Input},
+};
+
+/// Rows of keys, with a space for the gap no arm may ever point at
+struct Keypad {
+    keys: HashMap<char, Point>,
+    gap: Point,
+}
+
+impl Keypad {
+    fn new(rows: &[&str]) -> Self {
+        let mut keys = HashMap::new();
+        let mut gap = None;
+        for (y, row) in rows.iter().enumerate() {
+            for (x, c) in row.chars().enumerate() {
+                let pt = Point::new(x as i32, y as i32);
+                match c {
+                    ' ' => gap = Some(pt),
+                    _ => _ = keys.insert(c, pt),
+                }
+            }
+        }
+        Self {
+            keys,
+            gap: gap.expect("keypad has a gap"),
+        }
+    }
+
+    fn numeric() -> Self {
+        Self::new(&["789", "456", "123", " 0A"])
+    }
+
+    fn directional() -> Self {
+        Self::new(&[" ^A", "<v>"])
+    }
+
+    /// Ways to get from key `a` to key `b` and press it that could be optimal: all horizontal
+    /// moves then all vertical ones, or the other way round. Zig-zagging only costs the robots
+    /// further up the chain more, and a route through the gap is dropped.
+    fn routes(&self, a: char, b: char) -> Vec<String> {
+        let (from, to) = (self.keys[&a], self.keys[&b]);
+        let d = to - from;
+        let horizontal = if d.x > 0 { ">" } else { "<" }.repeat(d.x.unsigned_abs() as usize);
+        let vertical = if d.y > 0 { "v" } else { "^" }.repeat(d.y.unsigned_abs() as usize);
+        let mut routes = vec![];
+        if Point::new(to.x, from.y) != self.gap {
+            routes.push(format!("{horizontal}{vertical}A"));
+        }
+        if Point::new(from.x, to.y) != self.gap {
+            routes.push(format!("{vertical}{horizontal}A"));
+        }
+        routes.dedup();
+        routes
+    }
+}
+
+type CostTable = HashMap<(char, char), u64>;
+
+/// Presses needed at the human end to type `keys`, when each pair of consecutive keys costs
+/// `table[pair]`. Every arm starts (and ends) on `A`.
+fn sequence_cost(keys: &str, table: &CostTable) -> u64 {
+    std::iter::once('A')
+        .chain(keys.chars())
+        .tuple_windows()
+        .map(|pair| table[&pair])
+        .sum()
+}
+
+/// A chain of `depth` robots on directional keypads between the human and the robot at the
+/// numeric keypad. `tables[i]` is the cost of moving between two keys on the directional pad
+/// with `i` robots above it, and pressing the second; `tables[0]` is the human's own pad, where
+/// every press costs one.
+struct Robots {
+    depth: usize,
+    numeric: Keypad,
+    directional: Keypad,
+    tables: Vec<CostTable>,
+}
+
+impl Robots {
+    fn new(depth: usize) -> Self {
+        let directional = Keypad::directional();
+        let pairs = directional
+            .keys
+            .keys()
+            .copied()
+            .cartesian_product(directional.keys.keys().copied())
+            .collect_vec();
+        let mut tables = vec![pairs.iter().map(|&pair| (pair, 1)).collect::<CostTable>()];
+        for _ in 0..depth {
+            let below = tables.last().unwrap();
+            let next = pairs
+                .iter()
+                .map(|&(a, b)| {
+                    let cost = directional
+                        .routes(a, b)
+                        .iter()
+                        .map(|route| sequence_cost(route, below))
+                        .min()
+                        .unwrap();
+                    ((a, b), cost)
+                })
+                .collect();
+            tables.push(next);
+        }
+        Self {
+            depth,
+            numeric: Keypad::numeric(),
+            directional,
+            tables,
+        }
+    }
+
+    /// Cheapest route for each pair of `keys` on `pad`, priced with `tables[level]`
+    fn best_routes(&self, pad: &Keypad, keys: &str, level: usize) -> String {
+        std::iter::once('A')
+            .chain(keys.chars())
+            .tuple_windows()
+            .map(|(a, b)| {
+                pad.routes(a, b)
+                    .into_iter()
+                    .min_by_key(|route| sequence_cost(route, &self.tables[level]))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn code_cost(&self, code: &str) -> u64 {
+        std::iter::once('A')
+            .chain(code.chars())
+            .tuple_windows()
+            .map(|(a, b)| {
+                self.numeric
+                    .routes(a, b)
+                    .iter()
+                    .map(|route| sequence_cost(route, &self.tables[self.depth]))
+                    .min()
+                    .unwrap()
+            })
+            .sum()
+    }
+
+    /// The actual buttons the human presses. Its length is `code_cost`, so this is only
+    /// practical for a few layers.
+    fn presses(&self, code: &str) -> String {
+        let mut keys = self.best_routes(&self.numeric, code, self.depth);
+        for level in (0..self.depth).rev() {
+            keys = self.best_routes(&self.directional, &keys, level);
+        }
+        keys
+    }
+
+    fn complexity(&self, codes: &[&str]) -> u64 {
+        codes
+            .iter()
+            .map(|code| {
+                let numeric = code
+                    .trim_end_matches('A')
+                    .parse::<u64>()
+                    .expect("numeric code");
+                self.code_cost(code) * numeric
+            })
+            .sum()
+    }
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day21.txt");
+    let codes = input.lines().collect_vec();
+    for (part, depth) in [(1, 2), (2, 25)] {
+        let robots = Robots::new(depth);
+        if util::debug() && depth <= 3 {
+            for code in &codes {
+                println!("{code}: {}", robots.presses(code));
+            }
+        }
+        println!("Part {part}: {}", robots.complexity(&codes));
+    }
 }
 
 #[cfg(test)]
@@ -8,16 +189,40 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+029A
+980A
+179A
+456A
+379A";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let pad = Keypad::numeric();
+        assert_eq!(pad.keys[&'A'], Point::new(2, 3));
+        assert_eq!(pad.gap, Point::new(0, 3));
+        // going up first from the bottom row would pass over the gap
+        assert_eq!(pad.routes('A', '1'), vec!["^<<A"]);
+        assert_eq!(Keypad::directional().routes('A', 'A'), vec!["A"]);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        let robots = Robots::new(2);
+        let presses = robots.presses("029A");
+        assert_eq!(presses.len(), 68);
+        assert_eq!(robots.code_cost("029A"), 68);
+        assert_eq!(Robots::new(0).presses("029A").len(), "<A^A>^^AvvvA".len());
+        let codes = TEST_INPUT.lines().collect_vec();
+        assert_eq!(robots.complexity(&codes), 126384);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        let codes = TEST_INPUT.lines().collect_vec();
+        assert_eq!(Robots::new(25).complexity(&codes), 154115708116294);
+    }
 }
@@ -1,6 +1,100 @@
+use rayon::prelude::*;
+
+use crate::shared::util::Input;
+
+const PRUNE: u64 = 16_777_216;
+/// Price changes are in `-9..=9`, so a window of four fits in base 19
+const WINDOWS: usize = 19 * 19 * 19 * 19;
+
+/// The monkeys' secret number generator: an endless stream starting with the seed itself, so
+/// `.nth(2000)` is the 2000th new secret
+#[derive(Debug, Clone, Copy)]
+struct Secrets(u64);
+
+impl Secrets {
+    fn next_secret(secret: u64) -> u64 {
+        let mix_prune = |secret: u64, value: u64| (secret ^ value) % PRUNE;
+        let secret = mix_prune(secret, secret << 6);
+        let secret = mix_prune(secret, secret >> 5);
+        mix_prune(secret, secret << 11)
+    }
+}
+
+impl Iterator for Secrets {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        let cur = self.0;
+        self.0 = Self::next_secret(cur);
+        Some(cur)
+    }
+}
+
+fn parse_input(input: &str) -> Vec<u64> {
+    input
+        .lines()
+        .map(|l| l.parse().expect("secret should be a number"))
+        .collect()
+}
+
+fn part1(seeds: &[u64]) -> u64 {
+    seeds
+        .par_iter()
+        .map(|&seed| Secrets(seed).nth(2000).unwrap())
+        .sum()
+}
+
+/// Four consecutive price changes as a single index into a flat array
+fn window_index(changes: [i8; 4]) -> usize {
+    changes
+        .iter()
+        .fold(0, |idx, &c| idx * 19 + (c + 9) as usize)
+}
+
+/// Bananas per window across every buyer. A buyer sells at the first time a window shows up,
+/// which `seen` tracks by stamping each window with the buyer's number.
+fn add_buyer(totals: &mut [u32], seen: &mut [u32], buyer: u32, seed: u64) {
+    let prices = Secrets(seed)
+        .take(2001)
+        .map(|s| (s % 10) as i8)
+        .collect::<Vec<_>>();
+    for w in prices.windows(5) {
+        let idx = window_index([w[1] - w[0], w[2] - w[1], w[3] - w[2], w[4] - w[3]]);
+        if seen[idx] != buyer {
+            seen[idx] = buyer;
+            totals[idx] += w[4] as u32;
+        }
+    }
+}
+
+/// Each rayon job keeps its own totals (and stamps) and they're summed at the end
+fn part2(seeds: &[u64]) -> u32 {
+    let totals = seeds
+        .par_iter()
+        .enumerate()
+        .fold(
+            || (vec![0u32; WINDOWS], vec![0u32; WINDOWS]),
+            |(mut totals, mut seen), (i, &seed)| {
+                add_buyer(&mut totals, &mut seen, i as u32 + 1, seed);
+                (totals, seen)
+            },
+        )
+        .map(|(totals, _)| totals)
+        .reduce(
+            || vec![0u32; WINDOWS],
+            |mut a, b| {
+                a.iter_mut().zip(b).for_each(|(x, y)| *x += y);
+                a
+            },
+        );
+    totals.into_iter().max().unwrap_or(0)
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day22.txt");
+    let seeds = parse_input(&input);
+    println!("Part 1: {}", part1(&seeds));
+    println!("Part 2: {}", part2(&seeds));
 }
 
 #[cfg(test)]
@@ -8,16 +102,32 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+1
+10
+100
+2024";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        assert_eq!(parse_input(TEST_INPUT), vec![1, 10, 100, 2024]);
+        let secrets = Secrets(123).skip(1).take(3).collect::<Vec<_>>();
+        assert_eq!(secrets, vec![15887950, 16495136, 527345]);
+        assert_eq!(Secrets(123).nth(10), Some(5908254));
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        assert_eq!(part1(&parse_input(TEST_INPUT)), 37327623);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        assert_eq!(window_index([-9, -9, -9, -9]), 0);
+        assert_eq!(window_index([9, 9, 9, 9]), WINDOWS - 1);
+        assert_eq!(part2(&[1, 2, 3, 2024]), 23);
+    }
 }
@@ -1,6 +1,136 @@
+use std::collections::{HashMap, HashSet};
+
+use itertools::Itertools;
+use petgraph::{graph::NodeIndex, Graph, Undirected};
+
+use crate::shared::util::Input;
+
+struct Lan<'a> {
+    graph: Graph<&'a str, (), Undirected>,
+    /// Neighbors of every node as a set, for quick adjacency checks
+    adjacent: Vec<HashSet<NodeIndex>>,
+}
+
+impl<'a> Lan<'a> {
+    fn name(&self, node: NodeIndex) -> &'a str {
+        self.graph[node]
+    }
+
+    fn connected(&self, a: NodeIndex, b: NodeIndex) -> bool {
+        self.adjacent[a.index()].contains(&b)
+    }
+}
+
+fn parse_input(input: &str) -> Lan<'_> {
+    let mut graph = Graph::new_undirected();
+    let mut nodes = HashMap::new();
+    for line in input.lines() {
+        let (a, b) = line
+            .split_once('-')
+            .unwrap_or_else(|| panic!("expected a-b, got {line:?}"));
+        let [a, b] = [a, b].map(|name| *nodes.entry(name).or_insert_with(|| graph.add_node(name)));
+        graph.update_edge(a, b, ());
+    }
+    let adjacent = graph
+        .node_indices()
+        .map(|n| graph.neighbors(n).collect())
+        .collect();
+    Lan { graph, adjacent }
+}
+
+/// Every set of three computers that are all connected to each other, each listed once with
+/// its nodes in increasing index order
+fn triangles(lan: &Lan) -> Vec<[NodeIndex; 3]> {
+    lan.graph
+        .node_indices()
+        .flat_map(|a| {
+            lan.graph
+                .neighbors(a)
+                .filter(move |&b| b > a)
+                .flat_map(move |b| {
+                    lan.graph
+                        .neighbors(b)
+                        .filter(move |&c| c > b && lan.connected(a, c))
+                        .map(move |c| [a, b, c])
+                })
+        })
+        .collect()
+}
+
+fn part1(lan: &Lan) -> usize {
+    triangles(lan)
+        .into_iter()
+        .filter(|tri| tri.iter().any(|&n| lan.name(n).starts_with('t')))
+        .count()
+}
+
+/// Bron–Kerbosch with pivoting: grow the clique `r` from candidates `p`, with `x` holding
+/// nodes already covered by an earlier branch. Skipping the pivot's neighbors is safe because
+/// any maximal clique containing one of them either contains the pivot or another candidate
+/// that isn't its neighbor.
+fn bron_kerbosch(
+    lan: &Lan,
+    r: &mut Vec<NodeIndex>,
+    mut p: HashSet<NodeIndex>,
+    mut x: HashSet<NodeIndex>,
+    best: &mut Vec<NodeIndex>,
+) {
+    if p.is_empty() {
+        if x.is_empty() && r.len() > best.len() {
+            *best = r.clone();
+        }
+        return;
+    }
+    let pivot = *p
+        .union(&x)
+        .max_by_key(|&&u| lan.adjacent[u.index()].intersection(&p).count())
+        .expect("p is non-empty");
+    let candidates = p
+        .difference(&lan.adjacent[pivot.index()])
+        .copied()
+        .collect_vec();
+    for v in candidates {
+        let nbrs = &lan.adjacent[v.index()];
+        r.push(v);
+        bron_kerbosch(
+            lan,
+            r,
+            p.intersection(nbrs).copied().collect(),
+            x.intersection(nbrs).copied().collect(),
+            best,
+        );
+        r.pop();
+        p.remove(&v);
+        x.insert(v);
+    }
+}
+
+fn max_clique(lan: &Lan) -> Vec<NodeIndex> {
+    let mut best = vec![];
+    bron_kerbosch(
+        lan,
+        &mut vec![],
+        lan.graph.node_indices().collect(),
+        HashSet::new(),
+        &mut best,
+    );
+    best
+}
+
+/// Names in the biggest clique, sorted and joined with commas
+fn password(lan: &Lan) -> String {
+    max_clique(lan)
+        .into_iter()
+        .map(|n| lan.name(n))
+        .sorted()
+        .join(",")
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day23.txt");
+    let lan = parse_input(&input);
+    println!("Part 1: {}", part1(&lan));
+    println!("Part 2: {}", password(&lan));
 }
 
 #[cfg(test)]
@@ -8,16 +138,60 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+kh-tc
+qp-kh
+de-cg
+ka-co
+yn-aq
+qp-ub
+cg-tb
+vc-aq
+tb-ka
+wh-tc
+yn-cg
+kh-ub
+ta-co
+de-co
+tc-td
+tb-wq
+wh-td
+ta-ka
+td-qp
+aq-cg
+wq-ub
+ub-vc
+de-ta
+wq-aq
+wq-vc
+wh-yn
+ka-de
+kh-ta
+co-tc
+wh-qp
+tb-vc
+td-yn";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let lan = parse_input(TEST_INPUT);
+        assert_eq!(lan.graph.node_count(), 16);
+        assert_eq!(lan.graph.edge_count(), 32);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        let lan = parse_input(TEST_INPUT);
+        assert_eq!(triangles(&lan).len(), 12);
+        assert_eq!(part1(&lan), 7);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        let lan = parse_input(TEST_INPUT);
+        assert_eq!(password(&lan), "co,de,ka,ta");
+    }
 }
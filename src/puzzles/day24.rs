@@ -1,6 +1,267 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+    fs::{create_dir_all, write},
+};
+
+use itertools::Itertools;
+use nom::{
+    branch::alt,
+    bytes::complete::tag,
+    character::complete::{alphanumeric1, char, space0, space1},
+    combinator::{map, value},
+    sequence::{separated_pair, terminated, tuple},
+    IResult,
+};
+
+use crate::shared::{
+    image,
+    parse::{self, sections, ParseError},
+    util::Input,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Op {
+    And,
+    Or,
+    Xor,
+}
+
+impl Op {
+    fn apply(self, a: bool, b: bool) -> bool {
+        match self {
+            Op::And => a & b,
+            Op::Or => a | b,
+            Op::Xor => a ^ b,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Gate {
+    a: String,
+    op: Op,
+    b: String,
+    out: String,
+}
+
+impl Gate {
+    fn has_input(&self, wire: &str) -> bool {
+        self.a == wire || self.b == wire
+    }
+
+    /// Fed straight from the `x`/`y` inputs rather than from other gates
+    fn is_first_layer(&self) -> bool {
+        [&self.a, &self.b]
+            .iter()
+            .all(|w| w.starts_with('x') || w.starts_with('y'))
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Circuit {
+    inputs: HashMap<String, bool>,
+    gates: Vec<Gate>,
+}
+
+/// `x00: 1`, where the value has to be a single bit
+fn wire_value(input: &str) -> IResult<&str, (String, bool)> {
+    separated_pair(
+        map(alphanumeric1, str::to_owned),
+        terminated(char(':'), space0),
+        alt((value(false, char('0')), value(true, char('1')))),
+    )(input)
+}
+
+/// `x00 AND y00 -> z00`
+fn gate(input: &str) -> IResult<&str, Gate> {
+    let op = alt((
+        value(Op::And, tag("AND")),
+        value(Op::Or, tag("OR")),
+        value(Op::Xor, tag("XOR")),
+    ));
+    map(
+        tuple((
+            terminated(alphanumeric1, space1),
+            terminated(op, space1),
+            terminated(alphanumeric1, space1),
+            terminated(tag("->"), space1),
+            alphanumeric1,
+        )),
+        |(a, op, b, _, out): (&str, _, &str, _, &str)| Gate {
+            a: a.to_owned(),
+            op,
+            b: b.to_owned(),
+            out: out.to_owned(),
+        },
+    )(input)
+}
+
+fn parse_input(input: &str) -> parse::Result<Circuit> {
+    let [values, gates] = sections(input)[..] else {
+        return Err(ParseError {
+            line: 1,
+            text: input.lines().next().unwrap_or_default().to_owned(),
+            message: "expected wire values and gates separated by a blank line".to_owned(),
+        });
+    };
+    Ok(Circuit {
+        inputs: values.parse_lines(wire_value)?.into_iter().collect(),
+        gates: gates.parse_lines(gate)?,
+    })
+}
+
+impl Circuit {
+    /// Value of every wire, resolving gates in whatever order their inputs become known
+    fn evaluate(&self) -> HashMap<&str, bool> {
+        let mut values = self
+            .inputs
+            .iter()
+            .map(|(w, &v)| (w.as_str(), v))
+            .collect::<HashMap<_, _>>();
+        let mut pending = self.gates.iter().collect_vec();
+        while !pending.is_empty() {
+            let before = pending.len();
+            pending.retain(|gate| {
+                match (values.get(gate.a.as_str()), values.get(gate.b.as_str())) {
+                    (Some(&a), Some(&b)) => {
+                        values.insert(&gate.out, gate.op.apply(a, b));
+                        false
+                    }
+                    _ => true,
+                }
+            });
+            assert!(
+                pending.len() < before,
+                "circuit has a loop or a dangling wire"
+            );
+        }
+        values
+    }
+
+    /// The number on the wires starting with `prefix`, `prefix00` being the lowest bit
+    fn number(values: &HashMap<&str, bool>, prefix: char) -> u64 {
+        values
+            .iter()
+            .filter(|(w, _)| w.starts_with(prefix))
+            .sorted()
+            .rev()
+            .fold(0, |acc, (_, &bit)| acc << 1 | bit as u64)
+    }
+
+    fn output(&self) -> u64 {
+        Self::number(&self.evaluate(), 'z')
+    }
+
+    /// Gates whose output goes somewhere a ripple-carry adder wouldn't send it. Per bit `i`
+    /// the adder is
+    ///
+    /// ```text
+    /// s_i = x_i XOR y_i      c_i = x_i AND y_i
+    /// z_i = s_i XOR carry    t_i = s_i AND carry    carry' = c_i OR t_i
+    /// ```
+    ///
+    /// except bit 0, which is a half adder, and the top `z`, which is the last carry. So:
+    /// every `z` comes out of an XOR of internal wires (the top one out of an OR), an XOR of
+    /// internal wires always drives a `z`, a first-layer XOR always feeds another XOR, and every
+    /// AND feeds an OR. For bit 0 `x00 XOR y00` is `z00` itself, and `x00 AND y00` is the carry
+    /// into bit 1.
+    fn misplaced_outputs(&self) -> Vec<&str> {
+        let top_z = self
+            .gates
+            .iter()
+            .map(|g| g.out.as_str())
+            .filter(|w| w.starts_with('z'))
+            .max()
+            .unwrap_or("z00");
+        let feeds = |wire: &str, op: Op| self.gates.iter().any(|g| g.op == op && g.has_input(wire));
+        // the bit 0 carry has to meet the bit 1 sum in both the bit 1 XOR and AND
+        let sum_1 = self
+            .gates
+            .iter()
+            .find(|g| g.op == Op::Xor && g.is_first_layer() && g.has_input("x01"))
+            .map(|g| g.out.as_str());
+        let carries_bit_0 = |wire: &str| {
+            [Op::Xor, Op::And].into_iter().all(|op| {
+                self.gates.iter().any(|g| {
+                    g.op == op && g.has_input(wire) && sum_1.is_some_and(|s| g.has_input(s))
+                })
+            })
+        };
+        let wrong = self
+            .gates
+            .iter()
+            .filter(|g| {
+                let out = g.out.as_str();
+                if out.starts_with('z') {
+                    return if out == top_z {
+                        g.op != Op::Or
+                    } else if out == "z00" {
+                        g.op != Op::Xor || !g.has_input("x00")
+                    } else {
+                        g.op != Op::Xor || g.is_first_layer()
+                    };
+                }
+                match g.op {
+                    Op::Xor if !g.is_first_layer() => true,
+                    Op::Xor => g.has_input("x00") || !feeds(out, Op::Xor),
+                    Op::And if g.has_input("x00") => !carries_bit_0(out),
+                    Op::And => !feeds(out, Op::Or),
+                    Op::Or => false,
+                }
+            })
+            .map(|g| g.out.as_str())
+            .collect::<HashSet<_>>();
+        wrong.into_iter().sorted().collect()
+    }
+
+    /// Graphviz source with one node per gate, wires as labelled edges
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph circuit {\n    rankdir=LR;\n");
+        let driver = self
+            .gates
+            .iter()
+            .enumerate()
+            .map(|(i, g)| (g.out.as_str(), format!("g{i}")))
+            .collect::<HashMap<_, _>>();
+        for wire in self.inputs.keys().sorted() {
+            _ = writeln!(dot, "    {wire} [shape=circle];");
+        }
+        for (i, gate) in self.gates.iter().enumerate() {
+            _ = writeln!(dot, "    g{i} [shape=box, label=\"{:?}\"];", gate.op);
+            for input in [&gate.a, &gate.b] {
+                let from = driver.get(input.as_str()).map_or(input.as_str(), |g| g);
+                _ = writeln!(dot, "    {from} -> g{i} [label=\"{input}\"];");
+            }
+            if gate.out.starts_with('z') {
+                _ = writeln!(dot, "    {0} [shape=doublecircle];", gate.out);
+                _ = writeln!(dot, "    g{i} -> {};", gate.out);
+            }
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day24.txt");
+    let circuit = parse_input(&input).unwrap_or_else(|e| panic!("Error reading input: {e}"));
+    println!("Part 1: {}", circuit.output());
+    let misplaced = circuit.misplaced_outputs();
+    if misplaced.len() != 8 {
+        eprintln!(
+            "warning: expected 4 swapped pairs of wires, found {} suspect wires",
+            misplaced.len()
+        );
+    }
+    println!("Part 2: {}", misplaced.join(","));
+    if let Some(dir) = image::export_dir() {
+        let path = dir.join("day24.dot");
+        create_dir_all(dir)
+            .and_then(|_| write(&path, circuit.to_dot()))
+            .expect("write dot file");
+        println!("Circuit written to {}", path.display());
+    }
 }
 
 #[cfg(test)]
@@ -8,16 +269,134 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    const TEST_INPUT: &str = "\
+x00: 1
+x01: 0
+x02: 1
+x03: 1
+x04: 0
+y00: 1
+y01: 1
+y02: 1
+y03: 1
+y04: 1
+
+ntg XOR fgs -> mjb
+y02 OR x01 -> tnw
+kwq OR kpj -> z05
+x00 OR x03 -> fst
+tgd XOR rvg -> z01
+vdt OR tnw -> bfw
+bfw AND frj -> z10
+ffh OR nrd -> bqk
+y00 AND y03 -> djm
+y03 OR y00 -> psh
+bqk OR frj -> z08
+tnw OR fst -> frj
+gnj AND tgd -> z11
+bfw XOR mjb -> z00
+x03 OR x00 -> vdt
+gnj AND wpb -> z02
+x04 AND y00 -> kjc
+djm OR pbm -> qhw
+nrd AND vdt -> hwm
+kjc AND fst -> rvg
+y04 OR y02 -> fgs
+y01 AND x02 -> pbm
+ntg OR kjc -> kwq
+psh XOR fgs -> tgd
+qhw XOR tgd -> z09
+pbm OR djm -> kpj
+x03 XOR y03 -> ffh
+x00 XOR y04 -> ntg
+bfw OR bqk -> z06
+nrd XOR fgs -> wpb
+frj XOR qhw -> z04
+bqk OR frj -> z07
+y03 OR x01 -> nrd
+hwm AND bqk -> z03
+tgd XOR rvg -> z12
+tnw OR pbm -> gnj";
+
+    /// A correct `bits`-bit ripple-carry adder adding `x` and `y`
+    fn adder(bits: usize, x: u64, y: u64) -> String {
+        let mut lines = vec![];
+        for i in 0..bits {
+            lines.push(format!("x{i:02}: {}", x >> i & 1));
+            lines.push(format!("y{i:02}: {}", y >> i & 1));
+        }
+        lines.push(String::new());
+        lines.push("x00 XOR y00 -> z00".to_owned());
+        lines.push("x00 AND y00 -> c00".to_owned());
+        for i in 1..bits {
+            let carry = if i == 1 {
+                "c00".to_owned()
+            } else {
+                format!("r{:02}", i - 1)
+            };
+            lines.push(format!("x{i:02} XOR y{i:02} -> s{i:02}"));
+            lines.push(format!("x{i:02} AND y{i:02} -> c{i:02}"));
+            lines.push(format!("s{i:02} XOR {carry} -> z{i:02}"));
+            lines.push(format!("s{i:02} AND {carry} -> t{i:02}"));
+            let next = if i == bits - 1 {
+                format!("z{bits:02}")
+            } else {
+                format!("r{i:02}")
+            };
+            lines.push(format!("c{i:02} OR t{i:02} -> {next}"));
+        }
+        lines.join("\n")
+    }
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let circuit = parse_input(TEST_INPUT).unwrap();
+        assert_eq!(circuit.inputs.len(), 10);
+        assert_eq!(circuit.gates.len(), 36);
+        assert_eq!(circuit.gates[0].op, Op::Xor);
+        let dot = circuit.to_dot();
+        assert!(dot.contains("    g27 -> g0 [label=\"ntg\"];"));
+        assert!(dot.contains("    x00 -> g3 [label=\"x00\"];"));
+
+        let err = parse_input(&TEST_INPUT.replace("x03: 1", "x03: 2")).unwrap_err();
+        assert_eq!(err.line, 4);
+        let err = parse_input(&TEST_INPUT.replace("y00 AND y03", "y00 NAND y03")).unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (20, "y00 NAND y03 -> djm"));
+        assert_eq!(parse_input("x00: 1").unwrap_err().line, 1);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        assert_eq!(parse_input(TEST_INPUT).unwrap().output(), 2024);
+        assert_eq!(parse_input(&adder(8, 200, 99)).unwrap().output(), 299);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_part2() {
+        test_setup();
+        let circuit = parse_input(&adder(8, 0, 0)).unwrap();
+        assert!(circuit.misplaced_outputs().is_empty());
+        let swapped = adder(8, 0, 0)
+            .replace("-> z03", "-> tmp")
+            .replace("-> t03", "-> z03")
+            .replace("-> tmp", "-> t03")
+            .replace("-> c05", "-> tmp")
+            .replace("-> s05", "-> c05")
+            .replace("-> tmp", "-> s05");
+        let circuit = parse_input(&swapped).unwrap();
+        assert_eq!(circuit.misplaced_outputs(), ["c05", "s05", "t03", "z03"]);
+
+        // swaps touching bit 0 aren't excused by it being a half adder
+        let swapped = adder(8, 0, 0)
+            .replace("-> c00", "-> tmp")
+            .replace("-> c03", "-> c00")
+            .replace("-> tmp", "-> c03")
+            .replace("-> z00", "-> tmp")
+            .replace("-> s01", "-> z00")
+            .replace("-> tmp", "-> s01");
+        let circuit = parse_input(&swapped).unwrap();
+        assert_eq!(circuit.misplaced_outputs(), ["c00", "c03", "s01", "z00"]);
+    }
 }
@@ -1,6 +1,78 @@
+use crate::shared::util::Input;
+
+const PINS: usize = 5;
+/// Pin heights run from 0 to 5 between the full top and bottom rows
+const MAX_HEIGHT: u8 = 5;
+const SIDE: usize = MAX_HEIGHT as usize + 1;
+
+type Heights = [u8; PINS];
+
+/// Locks have their top row filled, keys their bottom row
+fn parse_input(input: &Input) -> (Vec<Heights>, Vec<Heights>) {
+    let mut locks = vec![];
+    let mut keys = vec![];
+    for section in input.sections() {
+        let rows = section.lines().collect::<Vec<_>>();
+        assert_eq!(
+            rows.len(),
+            MAX_HEIGHT as usize + 2,
+            "line {}: schematic should be 7 rows",
+            section.first_line
+        );
+        let mut heights = [0; PINS];
+        for row in &rows[1..rows.len() - 1] {
+            for (h, c) in heights.iter_mut().zip(row.chars()) {
+                *h += (c == '#') as u8;
+            }
+        }
+        if rows[0].chars().all(|c| c == '#') {
+            locks.push(heights);
+        } else {
+            keys.push(heights);
+        }
+    }
+    (locks, keys)
+}
+
+fn cell(heights: &Heights) -> usize {
+    heights
+        .iter()
+        .rev()
+        .fold(0, |idx, &h| idx * SIDE + h as usize)
+}
+
+/// For every height combination `h`, the number of keys with every pin at most `h`: key counts
+/// in a 6^5 table, prefix-summed along each pin. A lock then fits exactly the keys counted at
+/// `5 - lock`, so each lock is a single lookup no matter how many keys there are.
+fn key_table(keys: &[Heights]) -> Vec<u64> {
+    let mut table = vec![0u64; SIDE.pow(PINS as u32)];
+    for key in keys {
+        table[cell(key)] += 1;
+    }
+    for pin in 0..PINS {
+        let stride = SIDE.pow(pin as u32);
+        for idx in 0..table.len() {
+            if !(idx / stride).is_multiple_of(SIDE) {
+                table[idx] += table[idx - stride];
+            }
+        }
+    }
+    table
+}
+
+fn count_fits(locks: &[Heights], keys: &[Heights]) -> u64 {
+    let table = key_table(keys);
+    locks
+        .iter()
+        .map(|lock| table[cell(&lock.map(|h| MAX_HEIGHT - h))])
+        .sum()
+}
+
 pub fn solve() {
-    println!("Part 1: TODO!");
-    println!("Part 2: TODO!");
+    let input = Input::read("inputs/day25.txt");
+    let (locks, keys) = parse_input(&input);
+    println!("Part 1: {}", count_fits(&locks, &keys));
+    println!("Part 2: Merry Christmas!");
 }
 
 #[cfg(test)]
@@ -8,16 +80,87 @@ mod test {
     use super::*;
     use crate::shared::util::test_setup;
 
-    const TEST_INPUT: &str = "";
+    fn fits(lock: &Heights, key: &Heights) -> bool {
+        lock.iter().zip(key).all(|(l, k)| l + k <= MAX_HEIGHT)
+    }
+
+    const TEST_INPUT: &str = "\
+#####
+.####
+.####
+.####
+.#.#.
+.#...
+.....
+
+#####
+##.##
+.#.##
+...##
+...#.
+...#.
+.....
+
+.....
+#....
+#....
+#...#
+#.#.#
+#.###
+#####
+
+.....
+.....
+#.#..
+###..
+###.#
+###.#
+#####
+
+.....
+.....
+.....
+#....
+#.#..
+#.#.#
+#####";
 
     #[test]
-    fn test_parse() {}
+    fn test_parse() {
+        test_setup();
+        let (locks, keys) = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(locks, vec![[0, 5, 3, 4, 3], [1, 2, 0, 5, 3]]);
+        assert_eq!(
+            keys,
+            vec![[5, 0, 2, 1, 3], [4, 3, 4, 0, 2], [3, 0, 2, 0, 1]]
+        );
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part1() {}
+    fn test_part1() {
+        test_setup();
+        let (locks, keys) = parse_input(&Input::from(TEST_INPUT));
+        assert_eq!(count_fits(&locks, &keys), 3);
+    }
 
     #[test]
-    #[ignore = "wip"]
-    fn test_part2() {}
+    fn test_table_matches_pairwise() {
+        test_setup();
+        // every possible lock and a spread of keys, checked against the obvious double loop
+        let all = (0..SIDE.pow(PINS as u32))
+            .map(|mut idx| {
+                [0; PINS].map(|_| {
+                    let h = (idx % SIDE) as u8;
+                    idx /= SIDE;
+                    h
+                })
+            })
+            .collect::<Vec<_>>();
+        let keys = all.iter().step_by(7).copied().collect::<Vec<_>>();
+        let pairwise = all
+            .iter()
+            .map(|lock| keys.iter().filter(|key| fits(lock, key)).count() as u64)
+            .sum::<u64>();
+        assert_eq!(count_fits(&all, &keys), pairwise);
+    }
 }
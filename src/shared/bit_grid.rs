@@ -0,0 +1,301 @@
+use std::ops::{BitAnd, BitOr};
+
+const WORD: usize = u64::BITS as usize;
+
+/// Fixed-size occupancy grid packing each row into `u64` words, so width isn't capped at a
+/// single integer the way a `Vec<u128>` of rows is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BitGrid {
+    pub width: usize,
+    pub height: usize,
+    words_per_row: usize,
+    words: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(width: usize, height: usize) -> Self {
+        let words_per_row = width.div_ceil(WORD);
+        Self {
+            width,
+            height,
+            words_per_row,
+            words: vec![0; words_per_row * height],
+        }
+    }
+
+    fn row_words(&self, y: usize) -> &[u64] {
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    fn locate(&self, x: usize, y: usize) -> (usize, u64) {
+        assert!(
+            x < self.width && y < self.height,
+            "({x}, {y}) out of bounds"
+        );
+        (y * self.words_per_row + x / WORD, 1 << (x % WORD))
+    }
+
+    pub fn in_bounds(&self, x: i64, y: i64) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    pub fn set(&mut self, x: usize, y: usize) {
+        let (i, mask) = self.locate(x, y);
+        self.words[i] |= mask;
+    }
+
+    pub fn unset(&mut self, x: usize, y: usize) {
+        let (i, mask) = self.locate(x, y);
+        self.words[i] &= !mask;
+    }
+
+    pub fn test(&self, x: usize, y: usize) -> bool {
+        let (i, mask) = self.locate(x, y);
+        self.words[i] & mask != 0
+    }
+
+    /// Like [`BitGrid::test`], but anything outside the grid reads as unset
+    pub fn test_signed(&self, x: i64, y: i64) -> bool {
+        self.in_bounds(x, y) && self.test(x as usize, y as usize)
+    }
+
+    pub fn clear(&mut self) {
+        self.words.fill(0);
+    }
+
+    pub fn count(&self) -> usize {
+        self.words.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    pub fn row_count(&self, y: usize) -> usize {
+        self.row_words(y)
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn column_count(&self, x: usize) -> usize {
+        (0..self.height).filter(|&y| self.test(x, y)).count()
+    }
+
+    /// Every run of set cells in row `y` as `(start_x, len)`
+    pub fn row_runs(&self, y: usize) -> Vec<(usize, usize)> {
+        runs((0..self.width).map(|x| self.test(x, y)))
+    }
+
+    /// Every run of set cells in column `x` as `(start_y, len)`
+    pub fn column_runs(&self, x: usize) -> Vec<(usize, usize)> {
+        runs((0..self.height).map(|y| self.test(x, y)))
+    }
+
+    /// Longest horizontal run in row `y`, found a word at a time by repeatedly AND-ing the row
+    /// with itself shifted by one.
+    pub fn longest_row_run(&self, y: usize) -> usize {
+        let mut row = self.row_words(y).to_vec();
+        let mut len = 0;
+        while row.iter().any(|&w| w != 0) {
+            let shifted = shift_words(&row, -1);
+            row.iter_mut().zip(shifted).for_each(|(w, s)| *w &= s);
+            len += 1;
+        }
+        len
+    }
+
+    /// Longest horizontal run anywhere in the grid
+    pub fn longest_horizontal_run(&self) -> usize {
+        (0..self.height)
+            .map(|y| self.longest_row_run(y))
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Copy of the grid moved by `(dx, dy)`. Cells pushed past an edge are dropped. Rows are
+    /// shifted a word at a time and moved vertically as whole slices.
+    pub fn shifted(&self, dx: i64, dy: i64) -> Self {
+        let mut out = Self::new(self.width, self.height);
+        let tail = self.tail_mask();
+        for y in 0..self.height {
+            let ny = y as i64 + dy;
+            if !(0..self.height as i64).contains(&ny) {
+                continue;
+            }
+            let start = ny as usize * self.words_per_row;
+            let row = &mut out.words[start..start + self.words_per_row];
+            row.copy_from_slice(&shift_words(self.row_words(y), dx));
+            if let Some(last) = row.last_mut() {
+                *last &= tail;
+            }
+        }
+        out
+    }
+
+    /// Bits of the last word in each row that are inside the grid
+    fn tail_mask(&self) -> u64 {
+        match self.width % WORD {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    pub fn iter_set(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (0..self.height)
+            .flat_map(move |y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.test(x, y))
+    }
+
+    fn zip_words(&self, other: &Self, op: fn(u64, u64) -> u64) -> Self {
+        assert_eq!(
+            (self.width, self.height),
+            (other.width, other.height),
+            "grid sizes differ"
+        );
+        Self {
+            words: self
+                .words
+                .iter()
+                .zip(&other.words)
+                .map(|(&a, &b)| op(a, b))
+                .collect(),
+            ..self.clone()
+        }
+    }
+}
+
+/// Move a row (least significant bit = leftmost cell) `dx` cells to the right, or left for a
+/// negative `dx`, carrying bits across words. Bits shifted past either end are dropped.
+fn shift_words(row: &[u64], dx: i64) -> Vec<u64> {
+    let (words, bits) = (
+        dx.unsigned_abs() as usize / WORD,
+        dx.unsigned_abs() as usize % WORD,
+    );
+    let word = |i: Option<usize>| i.and_then(|i| row.get(i)).copied().unwrap_or(0);
+    (0..row.len())
+        .map(|i| {
+            // `near` lands on word i shifted by `bits`, `far` is the word beyond it that
+            // carries its top (or bottom) bits into i
+            let (near, far) = if dx >= 0 {
+                (i.checked_sub(words), i.checked_sub(words + 1))
+            } else {
+                (Some(i + words), Some(i + words + 1))
+            };
+            match (bits, dx >= 0) {
+                (0, _) => word(near),
+                (_, true) => (word(near) << bits) | (word(far) >> (WORD - bits)),
+                (_, false) => (word(near) >> bits) | (word(far) << (WORD - bits)),
+            }
+        })
+        .collect()
+}
+
+fn runs(cells: impl Iterator<Item = bool>) -> Vec<(usize, usize)> {
+    let mut runs = vec![];
+    let mut start = None;
+    let mut last = 0;
+    for (i, set) in cells.enumerate() {
+        match (set, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                runs.push((s, i - s));
+                start = None;
+            }
+            _ => {}
+        }
+        last = i + 1;
+    }
+    if let Some(s) = start {
+        runs.push((s, last - s));
+    }
+    runs
+}
+
+impl BitAnd for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitand(self, rhs: Self) -> Self::Output {
+        self.zip_words(rhs, |a, b| a & b)
+    }
+}
+
+impl BitOr for &BitGrid {
+    type Output = BitGrid;
+
+    fn bitor(self, rhs: Self) -> Self::Output {
+        self.zip_words(rhs, |a, b| a | b)
+    }
+}
+
+impl std::fmt::Display for BitGrid {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                write!(f, "{}", if self.test(x, y) { '#' } else { '.' })?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_runs_across_words() {
+        let mut grid = BitGrid::new(150, 3);
+        for x in 60..75 {
+            grid.set(x, 1);
+        }
+        grid.set(149, 1);
+        grid.set(3, 2);
+        assert_eq!(grid.count(), 17);
+        assert_eq!(grid.row_runs(1), vec![(60, 15), (149, 1)]);
+        assert_eq!(grid.longest_horizontal_run(), 15);
+        assert_eq!(grid.column_runs(149), vec![(1, 1)]);
+
+        let moved = grid.shifted(1, -1);
+        assert!(moved.test(61, 0) && !moved.test(60, 0));
+        assert_eq!(moved.row_count(0), 15); // (149, 1) fell off the right edge
+        assert_eq!((&grid | &moved).count(), 17 + 16);
+        assert_eq!((&grid & &moved).count(), 0);
+    }
+
+    #[test]
+    fn test_shift_matches_cells() {
+        let mut grid = BitGrid::new(150, 4);
+        for (x, y) in [
+            (0, 0),
+            (63, 0),
+            (64, 1),
+            (100, 2),
+            (127, 2),
+            (128, 3),
+            (149, 3),
+        ] {
+            grid.set(x, y);
+        }
+        for (dx, dy) in [
+            (0, 0),
+            (1, 0),
+            (-1, 1),
+            (64, -1),
+            (-64, 0),
+            (70, 2),
+            (-129, -3),
+            (150, 0),
+        ] {
+            let moved = grid.shifted(dx, dy);
+            let expected = grid
+                .iter_set()
+                .map(|(x, y)| (x as i64 + dx, y as i64 + dy))
+                .filter(|&(x, y)| grid.in_bounds(x, y))
+                .map(|(x, y)| (x as usize, y as usize))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                moved.iter_set().collect::<Vec<_>>(),
+                expected,
+                "({dx}, {dy})"
+            );
+        }
+    }
+}
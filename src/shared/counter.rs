@@ -0,0 +1,163 @@
+use std::{
+    collections::{hash_map, HashMap},
+    hash::Hash,
+};
+
+/// Multiset: how many times each key has been seen. Missing keys count as zero.
+#[derive(Debug, Clone)]
+pub struct Counter<K> {
+    counts: HashMap<K, usize>,
+}
+
+impl<K> Default for Counter<K> {
+    fn default() -> Self {
+        Self {
+            counts: HashMap::new(),
+        }
+    }
+}
+
+impl<K: Hash + Eq> PartialEq for Counter<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.counts == other.counts
+    }
+}
+
+impl<K: Hash + Eq> Eq for Counter<K> {}
+
+impl<K: Hash + Eq> Counter<K> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, key: K) {
+        self.add_n(key, 1);
+    }
+
+    pub fn add_n(&mut self, key: K, n: usize) {
+        if n > 0 {
+            *self.counts.entry(key).or_insert(0) += n;
+        }
+    }
+
+    pub fn get(&self, key: &K) -> usize {
+        self.counts.get(key).copied().unwrap_or(0)
+    }
+
+    pub fn remove(&mut self, key: &K) -> usize {
+        self.counts.remove(key).unwrap_or(0)
+    }
+
+    /// Number of distinct keys
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Sum of all counts
+    pub fn total(&self) -> usize {
+        self.counts.values().sum()
+    }
+
+    /// Add every count of `other` into this one
+    pub fn merge(&mut self, other: Counter<K>) {
+        for (key, n) in other {
+            self.add_n(key, n);
+        }
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&K, usize)> {
+        self.counts.iter().map(|(k, &n)| (k, n))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.counts.keys()
+    }
+
+    /// The `n` biggest counts, biggest first. Ties are broken by key so the result is stable.
+    pub fn most_common(&self, n: usize) -> Vec<(&K, usize)>
+    where
+        K: Ord,
+    {
+        let mut all = self.iter().collect::<Vec<_>>();
+        all.sort_by(|(ka, a), (kb, b)| b.cmp(a).then(ka.cmp(kb)));
+        all.truncate(n);
+        all
+    }
+
+    /// Rename every key, keeping its count. Keys that map to the same new key are summed.
+    pub fn map_keys<J, F>(self, mut f: F) -> Counter<J>
+    where
+        J: Hash + Eq,
+        F: FnMut(K) -> J,
+    {
+        self.flat_map_keys(|k| [f(k)])
+    }
+
+    /// Like [`Counter::map_keys`], but each key can turn into any number of keys, each of
+    /// which inherits the full count.
+    pub fn flat_map_keys<J, I, F>(self, mut f: F) -> Counter<J>
+    where
+        J: Hash + Eq,
+        I: IntoIterator<Item = J>,
+        F: FnMut(K) -> I,
+    {
+        let mut mapped = Counter::new();
+        for (key, n) in self {
+            for new_key in f(key) {
+                mapped.add_n(new_key, n);
+            }
+        }
+        mapped
+    }
+}
+
+impl<K: Hash + Eq> FromIterator<K> for Counter<K> {
+    fn from_iter<I: IntoIterator<Item = K>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<K: Hash + Eq> Extend<K> for Counter<K> {
+    fn extend<I: IntoIterator<Item = K>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|k| self.add(k));
+    }
+}
+
+impl<K> IntoIterator for Counter<K> {
+    type Item = (K, usize);
+    type IntoIter = hash_map::IntoIter<K, usize>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_counter() {
+        let mut counter = "abracadabra".chars().collect::<Counter<_>>();
+        assert_eq!(counter.get(&'a'), 5);
+        assert_eq!(counter.get(&'z'), 0);
+        assert_eq!(counter.total(), 11);
+        assert_eq!(counter.most_common(2), vec![(&'a', 5), (&'b', 2)]);
+
+        counter.merge("zz".chars().collect());
+        counter.add_n('c', 3);
+        assert_eq!(
+            counter.most_common(3),
+            vec![(&'a', 5), (&'c', 4), (&'b', 2)]
+        );
+
+        let vowels = counter.map_keys(|c| "aeiou".contains(c));
+        assert_eq!((vowels.get(&true), vowels.get(&false)), (5, 11));
+    }
+}
@@ -0,0 +1,133 @@
+use std::{collections::HashMap, hash::Hash};
+
+/// Shape of the orbit of a deterministic step function: after `start` steps the states repeat
+/// every `len` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The smallest step count that lands on the same state as step `n`
+    pub fn reduce(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+fn nth<S, F>(init: &S, step: &F, n: usize) -> S
+where
+    S: Clone,
+    F: Fn(&S) -> S,
+{
+    (0..n).fold(init.clone(), |s, _| step(&s))
+}
+
+/// Floyd's tortoise and hare. Constant memory, but needs `S: PartialEq` only.
+pub fn floyd<S, F>(init: &S, step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> S,
+{
+    let (mut tortoise, mut hare) = (step(init), step(&step(init)));
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&step(&hare));
+    }
+    let mut start = 0;
+    tortoise = init.clone();
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    let mut len = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        len += 1;
+    }
+    Cycle { start, len }
+}
+
+/// Brent's algorithm. Same answer as [`floyd`] in fewer steps.
+pub fn brent<S, F>(init: &S, step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> S,
+{
+    let (mut power, mut len) = (1, 1);
+    let mut tortoise = init.clone();
+    let mut hare = step(init);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+    let mut tortoise = init.clone();
+    let mut hare = nth(init, &step, len);
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, len }
+}
+
+/// Record every state until one repeats. Uses memory for the whole history, but each state is
+/// only stepped once and the history doubles as a lookup for [`Cycle::reduce`]d steps.
+pub fn hashed<S, F>(init: S, step: F) -> (Cycle, Vec<S>)
+where
+    S: Clone + Eq + Hash,
+    F: Fn(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = vec![];
+    let mut cur = init;
+    loop {
+        if let Some(&start) = seen.get(&cur) {
+            let len = history.len() - start;
+            return (Cycle { start, len }, history);
+        }
+        seen.insert(cur.clone(), history.len());
+        let next = step(&cur);
+        history.push(cur);
+        cur = next;
+    }
+}
+
+/// The state after `n` steps, for `n` far beyond what could be simulated directly
+pub fn state_at<S, F>(init: &S, step: F, n: usize) -> S
+where
+    S: Clone + PartialEq,
+    F: Fn(&S) -> S,
+{
+    let cycle = brent(init, &step);
+    nth(init, &step, cycle.reduce(n))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_cycle_finders_agree() {
+        // x -> x^2 + 1 mod 255 from 3 enters a cycle after a short tail
+        let step = |x: &u64| (x * x + 1) % 255;
+        let expected = hashed(3, step).0;
+        assert_eq!(floyd(&3, step), expected);
+        assert_eq!(brent(&3, step), expected);
+        let direct = (0..1000).fold(3, |x, _| step(&x));
+        assert_eq!(state_at(&3, step, 1000), direct);
+        assert_eq!(state_at(&3, step, 1), 10);
+    }
+}
@@ -0,0 +1,343 @@
+use std::{
+    collections::HashMap,
+    fs::{create_dir_all, File},
+    io::{self, BufWriter, Write},
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use super::render::{Color, Frame};
+
+static EXPORT_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Set once from `--export` by `main`
+pub fn configure(dir: Option<PathBuf>) {
+    if let Some(dir) = dir {
+        _ = EXPORT_DIR.set(dir);
+    }
+}
+
+/// The `--export` directory, for solutions that write something other than images
+pub fn export_dir() -> Option<&'static Path> {
+    EXPORT_DIR.get().map(PathBuf::as_path)
+}
+
+/// A frame sequence writing into the `--export` directory, or `None` if exporting is off.
+pub fn exporter(name: &str, export: ImageExport) -> Option<FrameSequence> {
+    EXPORT_DIR
+        .get()
+        .map(|dir| FrameSequence::new(dir, name, export))
+}
+
+pub type Rgb = (u8, u8, u8);
+
+impl Color {
+    pub fn rgb(self) -> Rgb {
+        match self {
+            Color::Red => (220, 50, 47),
+            Color::Green => (60, 180, 75),
+            Color::Yellow => (240, 200, 40),
+            Color::Blue => (38, 110, 210),
+            Color::Magenta => (200, 60, 180),
+            Color::Cyan => (40, 190, 200),
+            Color::White => (255, 255, 255),
+            Color::Gray => (128, 128, 128),
+        }
+    }
+}
+
+/// Maps glyphs to colours. Glyphs without an entry either get `fallback`, or with `auto` set a
+/// colour derived from the glyph itself, which is handy for "one colour per letter" maps.
+#[derive(Debug, Clone)]
+pub struct Palette {
+    colors: HashMap<char, Rgb>,
+    fallback: Rgb,
+    auto: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Self {
+            colors: HashMap::from([('.', (0, 0, 0)), ('#', (255, 255, 255))]),
+            fallback: (0, 200, 0),
+            auto: false,
+        }
+    }
+}
+
+impl Palette {
+    pub fn new(fallback: Rgb) -> Self {
+        Self {
+            colors: HashMap::new(),
+            fallback,
+            auto: false,
+        }
+    }
+
+    /// Spread unmapped glyphs across the colour wheel
+    pub fn auto() -> Self {
+        Self {
+            auto: true,
+            ..Self::new((0, 0, 0))
+        }
+    }
+
+    pub fn with(mut self, glyph: char, rgb: Rgb) -> Self {
+        self.colors.insert(glyph, rgb);
+        self
+    }
+
+    pub fn get(&self, glyph: char) -> Rgb {
+        match self.colors.get(&glyph) {
+            Some(&rgb) => rgb,
+            None if self.auto => hue_to_rgb((glyph as u32).wrapping_mul(47) % 360),
+            None => self.fallback,
+        }
+    }
+}
+
+/// Fully saturated colour for a hue in degrees
+fn hue_to_rgb(hue: u32) -> Rgb {
+    let x = (255 * (60 - (hue % 120).abs_diff(60)) / 60) as u8;
+    match hue / 60 {
+        0 => (255, x, 0),
+        1 => (x, 255, 0),
+        2 => (0, 255, x),
+        3 => (0, x, 255),
+        4 => (x, 0, 255),
+        _ => (255, 0, x),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Plain (ASCII) colour pixmap, `P3`
+    Ppm,
+    /// Plain (ASCII) greymap, `P2`
+    Pgm,
+    Svg,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Ppm => "ppm",
+            Format::Pgm => "pgm",
+            Format::Svg => "svg",
+        }
+    }
+}
+
+/// Turns a [`Frame`] into an image. Overlay colours on the frame win over the palette, so paths
+/// and highlights look the same as they do in the terminal.
+#[derive(Debug, Clone)]
+pub struct ImageExport {
+    pub format: Format,
+    pub cell_size: usize,
+    pub palette: Palette,
+}
+
+impl ImageExport {
+    pub fn new(format: Format) -> Self {
+        Self {
+            format,
+            cell_size: 4,
+            palette: Palette::default(),
+        }
+    }
+
+    pub fn cell_size(mut self, cell_size: usize) -> Self {
+        self.cell_size = cell_size.max(1);
+        self
+    }
+
+    pub fn palette(mut self, palette: Palette) -> Self {
+        self.palette = palette;
+        self
+    }
+
+    fn cell_rgb(&self, frame: &Frame, x: usize, y: usize) -> Rgb {
+        let cell = frame.cells()[y * frame.width + x];
+        match cell.color {
+            Some(color) => color.rgb(),
+            None => self.palette.get(cell.glyph),
+        }
+    }
+
+    pub fn write<W: Write>(&self, frame: &Frame, out: &mut W) -> io::Result<()> {
+        let size = self.cell_size;
+        let (width, height) = (frame.width * size, frame.height * size);
+        match self.format {
+            Format::Ppm | Format::Pgm => {
+                let grey = self.format == Format::Pgm;
+                writeln!(out, "{}", if grey { "P2" } else { "P3" })?;
+                writeln!(out, "{width} {height}\n255")?;
+                for y in 0..height {
+                    let mut samples = vec![];
+                    for x in 0..width {
+                        let (r, g, b) = self.cell_rgb(frame, x / size, y / size);
+                        if grey {
+                            // integer Rec. 601 luma
+                            samples.push((299 * r as u32 + 587 * g as u32 + 114 * b as u32) / 1000);
+                        } else {
+                            samples.extend([r as u32, g as u32, b as u32]);
+                        }
+                    }
+                    write_plain_row(out, &samples)?;
+                }
+            }
+            Format::Svg => {
+                let (r, g, b) = self.palette.get('.');
+                writeln!(
+                    out,
+                    r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+                )?;
+                writeln!(
+                    out,
+                    r#"<rect width="100%" height="100%" fill="rgb({r},{g},{b})"/>"#
+                )?;
+                for y in 0..frame.height {
+                    for x in 0..frame.width {
+                        let rgb = self.cell_rgb(frame, x, y);
+                        if rgb == (r, g, b) {
+                            continue;
+                        }
+                        writeln!(
+                            out,
+                            r#"<rect x="{}" y="{}" width="{size}" height="{size}" fill="rgb({},{},{})"/>"#,
+                            x * size,
+                            y * size,
+                            rgb.0,
+                            rgb.1,
+                            rgb.2
+                        )?;
+                    }
+                }
+                writeln!(out, "</svg>")?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, frame: &Frame, path: P) -> io::Result<()> {
+        let mut out = BufWriter::new(File::create(path)?);
+        self.write(frame, &mut out)?;
+        out.flush()
+    }
+}
+
+/// Plain netpbm lines must stay within 70 characters, so a pixel row spans as many lines as it
+/// needs. Each row still starts on a fresh line to keep small images readable.
+fn write_plain_row<W: Write>(out: &mut W, samples: &[u32]) -> io::Result<()> {
+    const MAX_LINE: usize = 70;
+    let mut line = String::new();
+    for sample in samples {
+        let sample = sample.to_string();
+        if !line.is_empty() && line.len() + 1 + sample.len() > MAX_LINE {
+            writeln!(out, "{line}")?;
+            line.clear();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(&sample);
+    }
+    writeln!(out, "{line}")
+}
+
+/// Writes frames as `<dir>/<name>_00000.<ext>`, `<dir>/<name>_00001.<ext>`, ...
+pub struct FrameSequence {
+    dir: PathBuf,
+    name: String,
+    export: ImageExport,
+    next: usize,
+}
+
+impl FrameSequence {
+    pub fn new<P: AsRef<Path>>(dir: P, name: &str, export: ImageExport) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+            name: name.to_owned(),
+            export,
+            next: 0,
+        }
+    }
+
+    pub fn write(&mut self, frame: &Frame) -> io::Result<PathBuf> {
+        create_dir_all(&self.dir)?;
+        let path = self.dir.join(format!(
+            "{}_{:05}.{}",
+            self.name,
+            self.next,
+            self.export.format.extension()
+        ));
+        self.export.save(frame, &path)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::shared::point::Point;
+
+    #[test]
+    fn test_plain_formats() {
+        let mut frame = Frame::from_fn(2, 1, |pt| if pt.x == 0 { '#' } else { '.' });
+        frame.highlight(&[Point::new(1, 0)]);
+        let mut ppm = Vec::new();
+        ImageExport::new(Format::Ppm)
+            .cell_size(1)
+            .write(&frame, &mut ppm)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(ppm).unwrap(),
+            "P3\n2 1\n255\n255 255 255 40 190 200\n"
+        );
+
+        let mut pgm = Vec::new();
+        ImageExport::new(Format::Pgm)
+            .cell_size(2)
+            .palette(Palette::new((0, 0, 0)).with('#', (100, 100, 100)))
+            .write(&Frame::from_fn(1, 1, |_| '#'), &mut pgm)
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(pgm).unwrap(),
+            "P2\n2 2\n255\n100 100\n100 100\n"
+        );
+
+        let mut wide = Vec::new();
+        ImageExport::new(Format::Ppm)
+            .cell_size(3)
+            .write(&Frame::from_fn(5, 2, |_| '#'), &mut wide)
+            .unwrap();
+        let wide = String::from_utf8(wide).unwrap();
+        assert!(wide.lines().all(|line| line.len() <= 70));
+        let samples = wide.lines().skip(3).flat_map(str::split_whitespace);
+        assert_eq!(samples.count(), 15 * 6 * 3);
+    }
+
+    #[test]
+    fn test_svg() {
+        let frame = Frame::from_fn(3, 2, |pt| if pt == Point::new(2, 1) { '#' } else { '.' });
+        let mut svg = Vec::new();
+        ImageExport::new(Format::Svg)
+            .cell_size(5)
+            .write(&frame, &mut svg)
+            .unwrap();
+        let svg = String::from_utf8(svg).unwrap();
+        let lines: Vec<_> = svg.lines().collect();
+        assert!(lines[0].starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg""#));
+        assert!(lines[0].contains(r#"width="15" height="10" viewBox="0 0 15 10""#));
+        // the background is one rect, then only the cells that differ from it
+        assert_eq!(
+            lines[1..lines.len() - 1],
+            [
+                r#"<rect width="100%" height="100%" fill="rgb(0,0,0)"/>"#,
+                r#"<rect x="10" y="5" width="5" height="5" fill="rgb(255,255,255)"/>"#,
+            ]
+        );
+        assert_eq!(lines.last(), Some(&"</svg>"));
+    }
+}
@@ -0,0 +1,170 @@
+use simple_grid::Grid;
+
+use super::{bit_grid::BitGrid, math::gcd, point::Point, sparse_grid::SparseGrid};
+
+/// Anything box-shaped that points can be inside of
+pub trait Bounds {
+    /// Inclusive `(min, max)` corners, `None` if nothing is inside
+    fn corners(&self) -> Option<(Point, Point)>;
+
+    fn contains_point(&self, pt: Point) -> bool {
+        self.corners()
+            .is_some_and(|(min, max)| pt.in_bounds(min, max))
+    }
+}
+
+/// Inclusive `(min, max)` corners
+impl Bounds for (Point, Point) {
+    fn corners(&self) -> Option<(Point, Point)> {
+        (self.0.x <= self.1.x && self.0.y <= self.1.y).then_some(*self)
+    }
+}
+
+/// `(w, h)` grid sizes as the corners of the box they fill
+fn sized(width: usize, height: usize) -> Option<(Point, Point)> {
+    (width > 0 && height > 0).then(|| {
+        (
+            Point::new(0, 0),
+            Point::new(width as i32 - 1, height as i32 - 1),
+        )
+    })
+}
+
+impl<T> Bounds for Grid<T> {
+    fn corners(&self) -> Option<(Point, Point)> {
+        sized(self.width(), self.height())
+    }
+}
+
+impl Bounds for BitGrid {
+    fn corners(&self) -> Option<(Point, Point)> {
+        sized(self.width, self.height)
+    }
+}
+
+/// Bounded by the box around its occupied cells
+impl<T> Bounds for SparseGrid<T> {
+    fn corners(&self) -> Option<(Point, Point)> {
+        self.bounds()
+    }
+}
+
+fn floor_div(a: i64, b: i64) -> i64 {
+    let q = a / b;
+    if a % b != 0 && (a < 0) != (b < 0) {
+        q - 1
+    } else {
+        q
+    }
+}
+
+/// Range of `k` for which `from + k * step` lies in `lo..=hi` along one axis
+fn steps_inside(from: i32, step: i32, lo: i32, hi: i32) -> (i64, i64) {
+    let (from, step, lo, hi) = (from as i64, step as i64, lo as i64, hi as i64);
+    match step.signum() {
+        0 if (lo..=hi).contains(&from) => (i64::MIN, i64::MAX),
+        0 => (1, 0),
+        1 => (-floor_div(from - lo, step), floor_div(hi - from, step)),
+        _ => (-floor_div(from - hi, step), floor_div(lo - from, step)),
+    }
+}
+
+/// The first of `from`, `from + step`, ... inside `bounds`, if the ray ever gets there
+fn first_inside<B: Bounds>(from: Point, step: Point, bounds: &B) -> Option<Point> {
+    let (min, max) = bounds.corners()?;
+    let (x_lo, x_hi) = steps_inside(from.x, step.x, min.x, max.x);
+    let (y_lo, y_hi) = steps_inside(from.y, step.y, min.y, max.y);
+    let k = x_lo.max(y_lo).max(0);
+    (k <= x_hi.min(y_hi)).then(|| {
+        let k = k as i32;
+        Point::new(from.x + k * step.x, from.y + k * step.y)
+    })
+}
+
+/// `from`, `from + step`, `from + 2 * step`, ... while inside `bounds`. If `from` is outside,
+/// the ray starts at the first of those points that is inside.
+pub fn ray<B: Bounds>(from: Point, step: Point, bounds: &B) -> impl Iterator<Item = Point> + '_ {
+    assert!(step != Point::new(0, 0), "ray needs a non-zero step");
+    std::iter::successors(first_inside(from, step, bounds), move |&pt| Some(pt + step))
+        .take_while(|&pt| bounds.contains_point(pt))
+}
+
+/// Every in-bounds point `a + k * (b - a)` for integer `k`, walking outwards from `a` towards
+/// `b` first and then away from it. Points strictly between `a` and `b` are skipped. Neither
+/// point has to be in bounds. Two equal points don't make a line, so `a == b` yields just `a`
+/// (if it's in bounds).
+pub fn line_through_raw<B: Bounds>(
+    a: Point,
+    b: Point,
+    bounds: &B,
+) -> impl Iterator<Item = Point> + '_ {
+    let step = b - a;
+    let zero = Point::new(0, 0);
+    let line = (step != zero)
+        .then(|| ray(a, step, bounds).chain(ray(a - step, zero - step, bounds)))
+        .into_iter()
+        .flatten();
+    let single = (step == zero && bounds.contains_point(a)).then_some(a);
+    line.chain(single)
+}
+
+/// Every in-bounds lattice point on the infinite line through `a` and `b`. The step is
+/// `(b - a)` divided by its gcd, so points between `a` and `b` are included too. Like
+/// [`line_through_raw`], `a == b` yields just `a`.
+pub fn line_through<B: Bounds>(a: Point, b: Point, bounds: &B) -> impl Iterator<Item = Point> + '_ {
+    let diff = b - a;
+    let div = gcd(diff.x, diff.y).max(1);
+    line_through_raw(a, a + Point::new(diff.x / div, diff.y / div), bounds)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_lines() {
+        let bounds = (Point::new(0, 0), Point::new(9, 9));
+        let mut raw = line_through_raw((2, 2).into(), (4, 6).into(), &bounds).collect::<Vec<_>>();
+        raw.sort_by_key(|p| (p.x, p.y));
+        assert_eq!(raw, vec![Point::new(2, 2), Point::new(4, 6)]);
+
+        let reduced = line_through((2, 2).into(), (4, 6).into(), &bounds).count();
+        assert_eq!(reduced, 5); // (1, 0) through (5, 8)
+
+        let horizontal = line_through((3, 1).into(), (7, 1).into(), &bounds).count();
+        assert_eq!(horizontal, 10);
+        let grid = BitGrid::new(4, 2);
+        assert_eq!(ray((0, 1).into(), (1, 0).into(), &grid).count(), 4);
+    }
+
+    #[test]
+    fn test_anchor_outside() {
+        let bounds = (Point::new(0, 0), Point::new(9, 9));
+        let diagonal = line_through((-3, -3).into(), (-2, -2).into(), &bounds).count();
+        assert_eq!(diagonal, 10);
+        let mut raw = line_through_raw((-4, 0).into(), (-2, 1).into(), &bounds).collect::<Vec<_>>();
+        raw.sort_by_key(|p| p.x);
+        assert_eq!(
+            raw,
+            [(0, 2), (2, 3), (4, 4), (6, 5), (8, 6)].map(Point::from)
+        );
+        // the grid is behind the anchor, so only the backwards half of the line reaches it
+        let behind = line_through((12, 3).into(), (13, 3).into(), &bounds).count();
+        assert_eq!(behind, 10);
+        assert_eq!(ray((-1, -1).into(), (-1, 0).into(), &bounds).count(), 0);
+        assert_eq!(
+            ray((20, 5).into(), (-3, 0).into(), &bounds).next(),
+            Some((8, 5).into())
+        );
+    }
+
+    #[test]
+    fn test_same_point() {
+        let bounds = (Point::new(0, 0), Point::new(9, 9));
+        let a = Point::new(3, 4);
+        assert_eq!(line_through(a, a, &bounds).collect::<Vec<_>>(), vec![a]);
+        assert_eq!(line_through_raw(a, a, &bounds).collect::<Vec<_>>(), vec![a]);
+        let outside = Point::new(-1, 4);
+        assert_eq!(line_through(outside, outside, &bounds).count(), 0);
+    }
+}
@@ -0,0 +1,285 @@
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+use super::math::{extended_gcd, gcd};
+
+/// Exact fraction, always stored in lowest terms with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    pub fn new(num: i128, den: i128) -> Self {
+        assert!(den != 0, "zero denominator");
+        let g = gcd(num, den).max(1) * den.signum();
+        Self {
+            num: num / g,
+            den: den / g,
+        }
+    }
+
+    pub fn numerator(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denominator(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.num == 0
+    }
+
+    pub fn to_integer(self) -> Option<i128> {
+        (self.den == 1).then_some(self.num)
+    }
+}
+
+impl From<i128> for Rational {
+    fn from(n: i128) -> Self {
+        Self { num: n, den: 1 }
+    }
+}
+
+impl std::fmt::Display for Rational {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.den {
+            1 => write!(f, "{}", self.num),
+            _ => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+impl Add for Rational {
+    type Output = Rational;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Rational::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+    }
+}
+
+impl Sub for Rational {
+    type Output = Rational;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        self + -rhs
+    }
+}
+
+impl Neg for Rational {
+    type Output = Rational;
+
+    fn neg(self) -> Self::Output {
+        Rational {
+            num: -self.num,
+            den: self.den,
+        }
+    }
+}
+
+impl Mul for Rational {
+    type Output = Rational;
+
+    fn mul(self, rhs: Self) -> Self::Output {
+        Rational::new(self.num * rhs.num, self.den * rhs.den)
+    }
+}
+
+impl Div for Rational {
+    type Output = Rational;
+
+    fn div(self, rhs: Self) -> Self::Output {
+        Rational::new(self.num * rhs.den, self.den * rhs.num)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Solution<T> {
+    Unique(Vec<T>),
+    /// Consistent, but with `free` variables left undetermined
+    Infinite {
+        free: usize,
+    },
+    None,
+}
+
+/// Solve `a * x = b` exactly by Gauss-Jordan elimination over the rationals. `a` is row-major
+/// and doesn't have to be square.
+pub fn solve(a: &[Vec<i128>], b: &[i128]) -> Solution<Rational> {
+    assert_eq!(a.len(), b.len(), "one right-hand side per row");
+    let cols = a.first().map_or(0, |row| row.len());
+    let mut rows = a
+        .iter()
+        .zip(b)
+        .map(|(row, &rhs)| {
+            assert_eq!(row.len(), cols, "ragged matrix");
+            row.iter()
+                .chain([&rhs])
+                .map(|&v| Rational::from(v))
+                .collect::<Vec<_>>()
+        })
+        .collect::<Vec<_>>();
+
+    let mut pivots = vec![];
+    for col in 0..cols {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&r| !rows[r][col].is_zero()) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let lead = rows[rank][col];
+        rows[rank].iter_mut().for_each(|v| *v = *v / lead);
+        let pivot_row = rows[rank].clone();
+        for (r, row) in rows.iter_mut().enumerate() {
+            let factor = row[col];
+            if r != rank && !factor.is_zero() {
+                row.iter_mut()
+                    .zip(&pivot_row)
+                    .for_each(|(v, &p)| *v = *v - factor * p);
+            }
+        }
+        pivots.push(col);
+    }
+
+    // a zero row with a non-zero right-hand side means 0 == c
+    if rows[pivots.len()..].iter().any(|row| !row[cols].is_zero()) {
+        return Solution::None;
+    }
+    if pivots.len() < cols {
+        return Solution::Infinite {
+            free: cols - pivots.len(),
+        };
+    }
+    Solution::Unique(rows[..cols].iter().map(|row| row[cols]).collect())
+}
+
+/// Like [`solve`], but a unique solution only counts if every value is an integer
+pub fn solve_integer(a: &[Vec<i128>], b: &[i128]) -> Solution<i128> {
+    match solve(a, b) {
+        Solution::Unique(values) => values
+            .into_iter()
+            .map(Rational::to_integer)
+            .collect::<Option<Vec<_>>>()
+            .map_or(Solution::None, Solution::Unique),
+        Solution::Infinite { free } => Solution::Infinite { free },
+        Solution::None => Solution::None,
+    }
+}
+
+/// Cheapest non-negative integer `(x, y)` with `x * u + y * v == target`, where a press of `u`
+/// costs `cost.0` and a press of `v` costs `cost.1`. Costs are expected to be non-negative.
+///
+/// When `u` and `v` are collinear there are infinitely many real solutions; the integer ones
+/// along the line are `(x0 + k * v', y0 - k * u')` (from extended Euclid), and since cost is
+/// linear in `k` the cheapest sits at one end of the feasible range of `k`.
+pub fn min_cost_2d(
+    u: (i128, i128),
+    v: (i128, i128),
+    target: (i128, i128),
+    cost: (i128, i128),
+) -> Option<(i128, i128)> {
+    let a = [vec![u.0, v.0], vec![u.1, v.1]];
+    match solve_integer(&a, &[target.0, target.1]) {
+        Solution::Unique(xy) => (xy[0] >= 0 && xy[1] >= 0).then_some((xy[0], xy[1])),
+        Solution::None => None,
+        Solution::Infinite { free: 2 } => Some((0, 0)), // all-zero system with a zero target
+        Solution::Infinite { .. } => {
+            // both rows are multiples of one equation; use whichever isn't all zero
+            let (p, q, t) = if (u.0, v.0) != (0, 0) {
+                (u.0, v.0, target.0)
+            } else {
+                (u.1, v.1, target.1)
+            };
+            min_cost_on_line(p, q, t, cost)
+        }
+    }
+}
+
+/// Cheapest non-negative integer solution of `p * x + q * y == t`
+fn min_cost_on_line(p: i128, q: i128, t: i128, cost: (i128, i128)) -> Option<(i128, i128)> {
+    if p == 0 || q == 0 {
+        // one variable is pinned and the other doesn't matter, so leave it at zero
+        let coef = p + q;
+        let pinned = (t % coef == 0 && t / coef >= 0).then_some(t / coef)?;
+        return Some(if q == 0 { (pinned, 0) } else { (0, pinned) });
+    }
+    let (g, s, r) = extended_gcd(p, q);
+    if t % g != 0 {
+        return None;
+    }
+    let (x0, y0) = (s * (t / g), r * (t / g));
+    let (dx, dy) = (q / g, -p / g); // (x, y) = (x0 + k * dx, y0 + k * dy)
+
+    // k range keeping one coordinate non-negative: base + k * step >= 0
+    let limit = |base: i128, step: i128| -> (Option<i128>, Option<i128>) {
+        if step > 0 {
+            (
+                Some((-base).div_euclid(step) + ((-base).rem_euclid(step) != 0) as i128),
+                None,
+            )
+        } else {
+            (None, Some(base.div_euclid(-step)))
+        }
+    };
+    let (lo_x, hi_x) = limit(x0, dx);
+    let (lo_y, hi_y) = limit(y0, dy);
+    let lo = lo_x.into_iter().chain(lo_y).max();
+    let hi = hi_x.into_iter().chain(hi_y).min();
+    if let (Some(lo), Some(hi)) = (lo, hi) {
+        if lo > hi {
+            return None;
+        }
+    }
+    let slope = cost.0 * dx + cost.1 * dy;
+    let k = match slope.signum() {
+        1 => lo?,
+        -1 => hi?,
+        _ => lo.or(hi)?,
+    };
+    Some((x0 + k * dx, y0 + k * dy))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let a = [vec![2, 1, -1], vec![-3, -1, 2], vec![-2, 1, 2]];
+        assert_eq!(
+            solve_integer(&a, &[8, -11, -3]),
+            Solution::Unique(vec![2, 3, -1])
+        );
+        assert_eq!(
+            solve(&[vec![2, 0], vec![0, 3]], &[1, 1]),
+            Solution::Unique(vec![Rational::new(1, 2), Rational::new(1, 3)])
+        );
+        assert_eq!(
+            solve_integer(&[vec![2, 0], vec![0, 3]], &[1, 1]),
+            Solution::None
+        );
+        assert_eq!(
+            solve(&[vec![1, 2], vec![2, 4]], &[3, 6]),
+            Solution::Infinite { free: 1 }
+        );
+        assert_eq!(solve(&[vec![1, 2], vec![2, 4]], &[3, 7]), Solution::None);
+    }
+
+    #[test]
+    fn test_min_cost_collinear() {
+        // B is twice A, so pressing A costs 3 and B costs 1: use as many B presses as possible
+        assert_eq!(min_cost_2d((1, 2), (2, 4), (7, 14), (3, 1)), Some((1, 3)));
+        // ...unless B gets expensive
+        assert_eq!(min_cost_2d((1, 2), (2, 4), (7, 14), (3, 7)), Some((7, 0)));
+        assert_eq!(min_cost_2d((2, 2), (4, 4), (7, 7), (3, 1)), None);
+        // regular claw machine
+        assert_eq!(
+            min_cost_2d((94, 34), (22, 67), (8400, 5400), (3, 1)),
+            Some((80, 40))
+        );
+    }
+}
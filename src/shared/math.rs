@@ -0,0 +1,169 @@
+use std::ops::{Add, Div, Mul, Rem, Sub};
+
+/// Signed integers the number-theory helpers work over
+pub trait Int:
+    Copy
+    + Ord
+    + std::fmt::Debug
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Rem<Output = Self>
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn abs(self) -> Self {
+        if self < Self::ZERO {
+            Self::ZERO - self
+        } else {
+            self
+        }
+    }
+
+    /// Remainder that is always in `0..m.abs()`
+    fn rem_euclid(self, m: Self) -> Self {
+        let r = self % m;
+        if r < Self::ZERO {
+            r + m.abs()
+        } else {
+            r
+        }
+    }
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(impl Int for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+        })*
+    };
+}
+
+impl_int!(i32, i64, i128);
+
+/// Always non-negative; `gcd(0, 0) == 0`
+pub fn gcd<T: Int>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != T::ZERO {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+pub fn lcm<T: Int>(a: T, b: T) -> T {
+    if a == T::ZERO || b == T::ZERO {
+        T::ZERO
+    } else {
+        (a / gcd(a, b) * b).abs()
+    }
+}
+
+/// Extended Euclid: `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`
+pub fn extended_gcd<T: Int>(a: T, b: T) -> (T, T, T) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_s, mut s) = (T::ONE, T::ZERO);
+    let (mut old_t, mut t) = (T::ZERO, T::ONE);
+    while r != T::ZERO {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_s, s) = (s, old_s - q * s);
+        (old_t, t) = (t, old_t - q * t);
+    }
+    if old_r < T::ZERO {
+        (T::ZERO - old_r, T::ZERO - old_s, T::ZERO - old_t)
+    } else {
+        (old_r, old_s, old_t)
+    }
+}
+
+/// `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime. `None` for `m <= 0`.
+pub fn mod_inverse<T: Int>(a: T, m: T) -> Option<T> {
+    if m <= T::ZERO {
+        return None;
+    }
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == T::ONE).then(|| x.rem_euclid(m))
+}
+
+/// `base^exp mod m` by repeated squaring. `(m - 1)^2` has to fit in `T`, so use `i128` for
+/// moduli beyond `i32`. `None` for `m <= 0` or a negative exponent.
+pub fn mod_pow<T: Int>(base: T, exp: T, m: T) -> Option<T> {
+    if m <= T::ZERO || exp < T::ZERO {
+        return None;
+    }
+    let two = T::ONE + T::ONE;
+    let (mut base, mut exp) = (base.rem_euclid(m), exp);
+    let mut result = T::ONE.rem_euclid(m);
+    while exp > T::ZERO {
+        if exp % two == T::ONE {
+            result = (result * base).rem_euclid(m);
+        }
+        base = (base * base).rem_euclid(m);
+        exp = exp / two;
+    }
+    Some(result)
+}
+
+/// Chinese Remainder Theorem over `(remainder, modulus)` pairs. Moduli don't have to be coprime.
+/// Returns `(x, lcm)` with `x` in `0..lcm` satisfying every congruence, or `None` if they
+/// contradict each other or a modulus isn't positive.
+pub fn crt<T: Int>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    congruences
+        .iter()
+        .try_fold((T::ZERO, T::ONE), |(r1, m1), &(r2, m2)| {
+            if m2 <= T::ZERO {
+                return None;
+            }
+            let (g, p, _) = extended_gcd(m1, m2);
+            let diff = r2 - r1;
+            if diff % g != T::ZERO {
+                return None;
+            }
+            let lcm = m1 / g * m2;
+            // r1 + m1 * k, where k solves m1 * k ≡ diff (mod m2)
+            let k = ((diff / g).rem_euclid(m2 / g) * p.rem_euclid(m2 / g)).rem_euclid(m2 / g);
+            Some(((r1 + m1 * k).rem_euclid(lcm), lcm))
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_euclid() {
+        assert_eq!(gcd(-12i64, 18), 6);
+        assert_eq!(lcm(4i64, 6), 12);
+        let (g, x, y) = extended_gcd(240i64, 46);
+        assert_eq!(g, 2);
+        assert_eq!(240 * x + 46 * y, 2);
+        assert_eq!(mod_inverse(3i64, 11), Some(4));
+        assert_eq!(mod_inverse(6i64, 9), None);
+        assert_eq!(mod_pow(4i64, 13, 497), Some(445));
+        assert_eq!(mod_pow(2i128, 100, 1_000_000_007), Some(976_371_285));
+    }
+
+    #[test]
+    fn test_bad_modulus() {
+        for m in [0i64, -7] {
+            assert_eq!(mod_inverse(3, m), None);
+            assert_eq!(mod_pow(3, 4, m), None);
+            assert_eq!(crt(&[(1, 3), (2, m)]), None);
+        }
+        assert_eq!(mod_pow(3i64, -1, 7), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2i64, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // day 14 style: period 101 in x, 103 in y
+        let (t, period) = crt(&[(12i64, 101), (45, 103)]).unwrap();
+        assert_eq!((t % 101, t % 103, period), (12, 45, 10403));
+        // non-coprime moduli
+        assert_eq!(crt(&[(2i128, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1i64, 4), (2, 6)]), None);
+    }
+}
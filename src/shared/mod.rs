@@ -1,4 +1,17 @@
+pub mod bit_grid;
+pub mod counter;
+pub mod cycle;
 pub mod graph_util;
 pub mod grid2d;
+pub mod image;
+pub mod lattice;
+pub mod linear;
+pub mod math;
+pub mod ordering;
+pub mod parse;
 pub mod point;
+pub mod regions;
+pub mod render;
+pub mod search;
+pub mod sparse_grid;
 pub mod util;
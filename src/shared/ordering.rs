@@ -0,0 +1,191 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A cycle in the "must come before" rules, as the pairs that form it, e.g. `[(1, 2), (2, 3),
+/// (3, 1)]`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CycleError<T> {
+    pub pairs: Vec<(T, T)>,
+}
+
+impl<T: std::fmt::Display> std::fmt::Display for CycleError<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ordering cycle:")?;
+        for (a, b) in &self.pairs {
+            write!(f, " {a}|{b}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: std::fmt::Debug + std::fmt::Display> std::error::Error for CycleError<T> {}
+
+/// Directed graph of `a` before `b` rules. Nothing says the rules are acyclic as a whole, only
+/// the parts of it that actually get sorted have to be.
+#[derive(Debug, Clone, Default)]
+pub struct PartialOrder<T> {
+    after: BTreeMap<T, BTreeSet<T>>,
+}
+
+impl<T: Copy + Ord> PartialOrder<T> {
+    pub fn from_pairs<I: IntoIterator<Item = (T, T)>>(pairs: I) -> Self {
+        let mut after: BTreeMap<T, BTreeSet<T>> = BTreeMap::new();
+        for (a, b) in pairs {
+            after.entry(a).or_default().insert(b);
+            after.entry(b).or_default();
+        }
+        Self { after }
+    }
+
+    /// Every value mentioned by any rule
+    pub fn nodes(&self) -> Vec<T> {
+        self.after.keys().copied().collect()
+    }
+
+    /// Whether there's a rule saying `a` comes before `b`
+    pub fn before(&self, a: T, b: T) -> bool {
+        self.after.get(&a).is_some_and(|set| set.contains(&b))
+    }
+
+    /// Every `(earlier, later)` pair in `seq` where a rule says `later` goes first
+    pub fn violations(&self, seq: &[T]) -> Vec<(T, T)> {
+        seq.iter()
+            .enumerate()
+            .flat_map(|(i, &a)| seq[i + 1..].iter().map(move |&b| (a, b)))
+            .filter(|&(a, b)| self.before(b, a))
+            .collect()
+    }
+
+    /// No value appears after something it has to come before
+    pub fn respects(&self, seq: &[T]) -> bool {
+        self.violations(seq).is_empty()
+    }
+
+    /// Only the rules between members of `subset`
+    pub fn induced(&self, subset: &[T]) -> Self {
+        let members = subset.iter().copied().collect::<BTreeSet<_>>();
+        let after = members
+            .iter()
+            .map(|&a| {
+                let next = self.after.get(&a).map_or_else(BTreeSet::new, |set| {
+                    set.intersection(&members).copied().collect()
+                });
+                (a, next)
+            })
+            .collect();
+        Self { after }
+    }
+
+    /// Reorder `subset` so it respects the rules between its own members (Kahn's algorithm).
+    /// Ties keep their order from `subset`, so an already valid sequence comes back unchanged.
+    pub fn toposort(&self, subset: &[T]) -> Result<Vec<T>, CycleError<T>> {
+        let graph = self.induced(subset);
+        let position = subset
+            .iter()
+            .enumerate()
+            .map(|(i, &v)| (v, i))
+            .collect::<BTreeMap<_, _>>();
+        let mut in_degree = graph
+            .after
+            .keys()
+            .map(|&v| (v, 0))
+            .collect::<BTreeMap<_, _>>();
+        for &b in graph.after.values().flatten() {
+            *in_degree.get_mut(&b).unwrap() += 1;
+        }
+
+        let mut ready = subset
+            .iter()
+            .filter(|v| in_degree[v] == 0)
+            .map(|&v| (position[&v], v))
+            .collect::<BTreeSet<_>>();
+        let mut sorted = Vec::with_capacity(subset.len());
+        while let Some((_, v)) = ready.pop_first() {
+            sorted.push(v);
+            for &next in &graph.after[&v] {
+                let deg = in_degree.get_mut(&next).unwrap();
+                *deg -= 1;
+                if *deg == 0 {
+                    ready.insert((position[&next], next));
+                }
+            }
+        }
+
+        if sorted.len() < graph.after.len() {
+            let stuck = in_degree
+                .into_iter()
+                .filter(|&(_, deg)| deg > 0)
+                .map(|(v, _)| v)
+                .collect::<BTreeSet<_>>();
+            return Err(graph.cycle_within(&stuck));
+        }
+        // duplicates in `subset` collapse to one node; put them back next to it
+        if sorted.len() < subset.len() {
+            let mut counts = BTreeMap::new();
+            subset
+                .iter()
+                .for_each(|v| *counts.entry(*v).or_insert(0) += 1);
+            sorted = sorted
+                .into_iter()
+                .flat_map(|v| std::iter::repeat_n(v, counts[&v]))
+                .collect();
+        }
+        Ok(sorted)
+    }
+
+    /// Some cycle in the whole rule set, if there is one
+    pub fn find_cycle(&self) -> Option<CycleError<T>> {
+        self.toposort(&self.nodes()).err()
+    }
+
+    /// Every node in `stuck` has a predecessor in `stuck` (that's why Kahn's couldn't place
+    /// it), so walking predecessors from anywhere must eventually repeat a node.
+    fn cycle_within(&self, stuck: &BTreeSet<T>) -> CycleError<T> {
+        let pred = |v: T| {
+            stuck
+                .iter()
+                .copied()
+                .find(|&u| self.before(u, v))
+                .expect("stuck node has a stuck predecessor")
+        };
+        let mut walk = vec![*stuck.first().expect("stuck set is non-empty")];
+        let mut seen = BTreeMap::from([(walk[0], 0)]);
+        loop {
+            let prev = pred(*walk.last().unwrap());
+            if let Some(&start) = seen.get(&prev) {
+                // walk[start..] goes backwards along the edges
+                let mut nodes = walk.split_off(start);
+                nodes.reverse();
+                let pairs = nodes
+                    .iter()
+                    .zip(nodes.iter().cycle().skip(1))
+                    .map(|(&a, &b)| (a, b))
+                    .collect();
+                return CycleError { pairs };
+            }
+            seen.insert(prev, walk.len());
+            walk.push(prev);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_toposort_subset() {
+        let order = PartialOrder::from_pairs([(1, 2), (2, 3), (3, 1), (4, 1)]);
+        assert!(order.respects(&[4, 1, 2]));
+        assert_eq!(order.violations(&[2, 1, 4]), vec![(2, 1), (1, 4)]);
+        // the rules loop, but {4, 1, 2} on its own is fine
+        assert_eq!(order.toposort(&[2, 1, 4]), Ok(vec![4, 1, 2]));
+
+        let cycle = order.find_cycle().unwrap();
+        assert_eq!(cycle.pairs.len(), 3);
+        assert!(cycle.pairs.iter().all(|&(a, b)| order.before(a, b)));
+        assert_eq!(
+            order.toposort(&[1, 2, 3]).unwrap_err().to_string(),
+            "ordering cycle: 2|3 3|1 1|2"
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::str::FromStr;
+
+use nom::{
+    bytes::complete::{is_not, tag, take_until},
+    character::complete::{char, digit1, one_of, space0, space1},
+    combinator::{all_consuming, map_res, opt, recognize},
+    multi::separated_list1,
+    sequence::{delimited, pair, separated_pair, terminated},
+    IResult, Parser,
+};
+
+/// Parse failure pointing at the offending (1-based) line of the original input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub text: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {} in {:?}", self.line, self.message, self.text)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+/// Decimal integer with an optional `+`/`-` sign, into any integer type
+pub fn integer<T: FromStr>(input: &str) -> IResult<&str, T> {
+    map_res(recognize(pair(opt(one_of("+-")), digit1)), str::parse)(input)
+}
+
+/// Integers separated by `sep` (with optional spaces around it). A blank `sep` means "one or
+/// more spaces".
+pub fn number_list<'a, T: FromStr>(
+    sep: &'a str,
+) -> impl FnMut(&'a str) -> IResult<&'a str, Vec<T>> {
+    move |input| {
+        if sep.trim().is_empty() {
+            separated_list1(space1, integer)(input)
+        } else {
+            separated_list1(delimited(space0, tag(sep.trim()), space0), integer)(input)
+        }
+    }
+}
+
+/// `x,y` (spaces around the comma allowed)
+pub fn coord<T: FromStr>(input: &str) -> IResult<&str, (T, T)> {
+    separated_pair(integer, delimited(space0, char(','), space0), integer)(input)
+}
+
+/// Every integer in arbitrary text, e.g. `p=0,4 v=3,-3` gives `[0, 4, 3, -3]`. A `-` straight
+/// after a digit is read as a sign too, so `1-3` gives `[1, -3]`. A number that doesn't fit `T`
+/// (too big, or negative for an unsigned type) is an error pointing at its line, rather than
+/// being dropped and shifting every later value along.
+pub fn ints<T: FromStr>(text: &str) -> Result<Vec<T>> {
+    ints_from(text, 1)
+}
+
+fn ints_from<T: FromStr>(text: &str, first_line: usize) -> Result<Vec<T>> {
+    let mut found = vec![];
+    for (idx, line) in text.lines().enumerate() {
+        let mut rest = line;
+        while !rest.is_empty() {
+            let token: IResult<&str, &str> = recognize(pair(opt(one_of("+-")), digit1))(rest);
+            match token {
+                Ok((remaining, token)) => {
+                    found.push(token.parse().map_err(|_| ParseError {
+                        line: first_line + idx,
+                        text: line.to_owned(),
+                        message: format!("{token} is out of range"),
+                    })?);
+                    rest = remaining;
+                }
+                Err(_) => {
+                    let skip = rest.chars().next().map_or(1, char::len_utf8);
+                    rest = &rest[skip..];
+                }
+            }
+        }
+    }
+    Ok(found)
+}
+
+/// A chunk of input between blank lines, remembering where it started so errors inside it can
+/// still point at the right line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// 1-based line number of the first line
+    pub first_line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    pub fn lines(&self) -> std::str::Lines<'a> {
+        self.text.lines()
+    }
+
+    /// Every integer in the section, see [`ints`]
+    pub fn ints<T: FromStr>(&self) -> Result<Vec<T>> {
+        ints_from(self.text, self.first_line)
+    }
+
+    pub fn parse_lines<T, P>(&self, parser: P) -> Result<Vec<T>>
+    where
+        P: Parser<&'a str, T, nom::error::Error<&'a str>>,
+    {
+        lines_from(self.text, self.first_line, parser)
+    }
+}
+
+/// Split on blank lines. Runs of blank lines count as one separator, and leading or trailing
+/// ones are dropped.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut found = vec![];
+    let mut start: Option<(usize, usize)> = None; // (byte offset, line number)
+    let mut offset = 0;
+    for (idx, line) in input.split_inclusive('\n').enumerate() {
+        let blank = line.trim().is_empty();
+        match (blank, start) {
+            (false, None) => start = Some((offset, idx + 1)),
+            (true, Some((begin, first_line))) => {
+                found.push(Section {
+                    first_line,
+                    text: input[begin..offset].trim_end(),
+                });
+                start = None;
+            }
+            _ => {}
+        }
+        offset += line.len();
+    }
+    if let Some((begin, first_line)) = start {
+        found.push(Section {
+            first_line,
+            text: input[begin..].trim_end(),
+        });
+    }
+    found
+}
+
+fn lines_from<'a, T, P>(input: &'a str, first_line: usize, mut parser: P) -> Result<Vec<T>>
+where
+    P: Parser<&'a str, T, nom::error::Error<&'a str>>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(idx, line)| {
+            all_consuming(|i| parser.parse(i))(line.trim_end())
+                .map(|(_, value)| value)
+                .map_err(|e| ParseError {
+                    line: first_line + idx,
+                    text: line.to_owned(),
+                    message: match e {
+                        nom::Err::Error(e) | nom::Err::Failure(e) => {
+                            format!("expected {:?} at {:?}", e.code, e.input)
+                        }
+                        nom::Err::Incomplete(_) => "incomplete input".to_owned(),
+                    },
+                })
+        })
+        .collect()
+}
+
+/// Run `parser` over every line, which it has to consume completely
+pub fn parse_lines<'a, T, P>(input: &'a str, parser: P) -> Result<Vec<T>>
+where
+    P: Parser<&'a str, T, nom::error::Error<&'a str>>,
+{
+    lines_from(input, 1, parser)
+}
+
+/// Lines of `key: value value ...`, e.g. `190: 10 19` or `x00: 1`
+pub fn key_values<K: FromStr, V: FromStr>(input: &str) -> Result<Vec<(K, Vec<V>)>> {
+    fn value_list<V: FromStr>(input: &str) -> IResult<&str, Vec<V>> {
+        separated_list1(space1, map_res(is_not(" \t"), str::parse))(input)
+    }
+    parse_lines(
+        input,
+        separated_pair(
+            map_res(take_until(":"), |k: &str| k.trim().parse::<K>()),
+            terminated(char(':'), space0),
+            value_list,
+        ),
+    )
+}
+
+/// One `x,y` pair per line
+pub fn coords<T: FromStr>(input: &str) -> Result<Vec<(T, T)>> {
+    parse_lines(input, coord)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_sections_keep_line_numbers() {
+        let input = "1|2\n3|4\n\n\n1,2,x\n4,5\n";
+        let parts = sections(input);
+        assert_eq!(parts.len(), 2);
+        assert_eq!(parts[0].text, "1|2\n3|4");
+        let pairs = parts[0]
+            .parse_lines(separated_pair(integer::<u32>, char('|'), integer))
+            .unwrap();
+        assert_eq!(pairs, vec![(1, 2), (3, 4)]);
+        let err = parts[1].parse_lines(number_list::<u32>(",")).unwrap_err();
+        assert_eq!(err.line, 5);
+        assert_eq!(err.text, "1,2,x");
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(ints::<i32>("p=0,4 v=3,-3").unwrap(), vec![0, 4, 3, -3]);
+        assert_eq!(ints::<u64>("Button A: X+94, Y+34").unwrap(), vec![94, 34]);
+        let err = ints::<u8>("12\nA: 300 12").unwrap_err();
+        assert_eq!((err.line, err.text.as_str()), (2, "A: 300 12"));
+        assert!(ints::<u64>("-3 4").is_err());
+        let section = sections("\n\n1 2\n3 -4")[0];
+        assert_eq!(section.ints::<i8>().unwrap(), vec![1, 2, 3, -4]);
+        assert_eq!(section.ints::<u8>().unwrap_err().line, 4);
+        assert_eq!(
+            key_values::<u64, u64>("190: 10 19\n3267: 81 40 27").unwrap()[1],
+            (3267, vec![81, 40, 27])
+        );
+        assert_eq!(coords::<u32>("5,4\n4, 2").unwrap(), vec![(5, 4), (4, 2)]);
+        assert_eq!(coords::<u32>("5,4\n4;2").unwrap_err().line, 2);
+        assert_eq!(
+            parse_lines("7 6  4\n1 2", number_list::<u8>(" ")).unwrap(),
+            vec![vec![7, 6, 4], vec![1, 2]]
+        );
+    }
+}
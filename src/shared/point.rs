@@ -1,6 +1,8 @@
 use anyhow::anyhow;
 use simple_grid::GridIndex;
 
+use super::grid2d::Direction;
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq)]
 pub struct Point {
     pub x: i32,
@@ -64,6 +66,12 @@ impl Point {
         (self.x as f32 / dist, self.y as f32 / dist)
     }
 
+    /// The neighboring point one step towards `dir`
+    pub fn step(&self, dir: Direction) -> Self {
+        let (dx, dy) = dir.offset();
+        (self.x + dx, self.y + dy).into()
+    }
+
     pub fn cardinal_neighbors(&self) -> [Self; 4] {
         [
             (self.x, self.y - 1).into(), // up
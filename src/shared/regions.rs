@@ -0,0 +1,217 @@
+use std::collections::{HashSet, VecDeque};
+
+use simple_grid::Grid;
+
+use super::{grid2d::Direction, point::Point, sparse_grid::SparseGrid};
+
+/// A 4-connected group of cells that are all "the same" under whatever equivalence was used to
+/// label the grid, keeping each cell's value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region<T> {
+    pub label: usize,
+    pub cells: SparseGrid<T>,
+}
+
+impl<T> Region<T> {
+    pub fn contains(&self, pt: &Point) -> bool {
+        self.cells.contains(pt)
+    }
+
+    pub fn area(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Number of cell edges between this region and anything else (including the grid edge)
+    pub fn perimeter(&self) -> usize {
+        self.cells
+            .points()
+            .flat_map(|pt| pt.cardinal_neighbors())
+            .filter(|nbr| !self.contains(nbr))
+            .count()
+    }
+
+    fn fenced(&self, pt: Point, dir: Direction) -> bool {
+        self.contains(&pt) && !self.contains(&pt.step(dir))
+    }
+
+    /// Number of straight fence sides, holes included. For each direction, every cell with a
+    /// fence on that side starts a new side unless the cell beside it (perpendicular to the
+    /// fence) has the same fence:
+    /// ```text
+    /// RRRRII  the up-facing fences of R are on (0..=3, 0) and (4, 2), but only
+    /// RRRRII  (0, 0) and (4, 2) have no up-fenced R cell to their left, so that's
+    /// VVRRRC  two up-facing sides
+    /// ```
+    pub fn sides(&self) -> usize {
+        Direction::ALL
+            .iter()
+            .map(|&dir| {
+                self.cells
+                    .points()
+                    .filter(|&&pt| {
+                        self.fenced(pt, dir) && !self.fenced(pt.step(dir.turn_left()), dir)
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Number of convex plus concave corners. Every closed fence alternates between horizontal
+    /// and vertical sides, so this always equals [`Region::sides`].
+    pub fn corners(&self) -> usize {
+        self.cells
+            .points()
+            .map(|&pt| {
+                Direction::ALL
+                    .iter()
+                    .filter(|&&dir| {
+                        let (a, b) = (pt.step(dir), pt.step(dir.turn_right()));
+                        let diag = a.step(dir.turn_right());
+                        match (self.contains(&a), self.contains(&b)) {
+                            (false, false) => true,
+                            (true, true) => !self.contains(&diag),
+                            _ => false,
+                        }
+                    })
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Inclusive `(min, max)` corners
+    pub fn bounding_box(&self) -> (Point, Point) {
+        self.cells.bounds().expect("regions are never empty")
+    }
+}
+
+/// Every connected component of a grid, and which component each cell belongs to
+#[derive(Debug, Clone)]
+pub struct Components<T> {
+    pub width: usize,
+    pub height: usize,
+    labels: Vec<usize>,
+    pub regions: Vec<Region<T>>,
+}
+
+impl<T> Components<T> {
+    pub fn label_of(&self, pt: Point) -> Option<usize> {
+        self.in_bounds(pt)
+            .then(|| self.labels[pt.y as usize * self.width + pt.x as usize])
+    }
+
+    pub fn region_at(&self, pt: Point) -> Option<&Region<T>> {
+        self.label_of(pt).map(|label| &self.regions[label])
+    }
+
+    fn in_bounds(&self, pt: Point) -> bool {
+        pt.in_bounds(
+            (0, 0).into(),
+            (self.width as i32 - 1, self.height as i32 - 1).into(),
+        )
+    }
+
+    /// True if `inner` can't reach the edge of the grid without crossing `outer`, i.e. `outer`
+    /// completely surrounds it (possibly along with other regions inside `outer`).
+    pub fn is_enclosed_by(&self, inner: usize, outer: usize) -> bool {
+        if inner == outer {
+            return false;
+        }
+        let mut seen = self.regions[inner]
+            .cells
+            .points()
+            .copied()
+            .collect::<HashSet<_>>();
+        let mut queue = seen.iter().copied().collect::<VecDeque<_>>();
+        while let Some(pt) = queue.pop_front() {
+            for nbr in pt.cardinal_neighbors() {
+                if !self.in_bounds(nbr) {
+                    return false; // escaped off the edge
+                }
+                if self.label_of(nbr) != Some(outer) && seen.insert(nbr) {
+                    queue.push_back(nbr);
+                }
+            }
+        }
+        true
+    }
+}
+
+/// Label every 4-connected component of `grid`, where neighbors join the same component when
+/// `same` says so. Labels are handed out in row-major order of each component's first cell.
+pub fn connected_components<T, F>(grid: &Grid<T>, mut same: F) -> Components<T>
+where
+    T: Clone,
+    F: FnMut(&T, &T) -> bool,
+{
+    let (width, height) = (grid.width(), grid.height());
+    let mut labels = vec![usize::MAX; width * height];
+    let mut regions = vec![];
+    let idx = |pt: Point| pt.y as usize * width + pt.x as usize;
+    let at = |pt: Point| grid.get((pt.x as usize, pt.y as usize)).unwrap();
+    let max = Point::new(width as i32 - 1, height as i32 - 1);
+    for start in grid.indices().map(Point::from) {
+        if labels[idx(start)] != usize::MAX {
+            continue;
+        }
+        let label = regions.len();
+        let mut cells = SparseGrid::new();
+        cells.insert(start, at(start).clone());
+        let mut queue = VecDeque::from([start]);
+        labels[idx(start)] = label;
+        while let Some(cur) = queue.pop_front() {
+            for nbr in cur.cardinal_neighbors() {
+                if nbr.in_bounds((0, 0).into(), max)
+                    && labels[idx(nbr)] == usize::MAX
+                    && same(at(cur), at(nbr))
+                {
+                    labels[idx(nbr)] = label;
+                    cells.insert(nbr, at(nbr).clone());
+                    queue.push_back(nbr);
+                }
+            }
+        }
+        regions.push(Region { label, cells });
+    }
+    Components {
+        width,
+        height,
+        labels,
+        regions,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+AAAAAA
+AAABBA
+AAABBA
+ABBAAA
+ABBAAA
+AAAAAA";
+
+    #[test]
+    fn test_holes() {
+        let width = TEST_INPUT.lines().next().unwrap().len();
+        let grid = Grid::new(
+            width,
+            TEST_INPUT.lines().count(),
+            TEST_INPUT.lines().flat_map(|l| l.chars()).collect(),
+        );
+        let components = connected_components(&grid, |a, b| a == b);
+        assert_eq!(components.regions.len(), 3);
+        let outer = components.region_at((0, 0).into()).unwrap();
+        assert_eq!(outer.area(), 28);
+        assert_eq!(outer.sides(), 12);
+        assert_eq!(outer.corners(), 12);
+        assert_eq!(outer.perimeter(), 40);
+        assert_eq!(outer.bounding_box(), ((0, 0).into(), (5, 5).into()));
+        let inner = components.region_at((1, 3).into()).unwrap();
+        assert_eq!(inner.cells.to_string(), "BB\nBB\n");
+        let b = components.label_of((4, 1).into()).unwrap();
+        assert!(components.is_enclosed_by(b, outer.label));
+        assert!(!components.is_enclosed_by(outer.label, b));
+    }
+}
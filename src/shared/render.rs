@@ -0,0 +1,329 @@
+use std::{
+    io::{self, stdin, stdout, ErrorKind, IsTerminal, Stdout, Write},
+    sync::OnceLock,
+    time::Duration,
+};
+
+use simple_grid::Grid;
+
+use super::point::Point;
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Playback options, filled in once from the command line by `main`
+#[derive(Debug, Clone, Copy)]
+pub struct Settings {
+    pub visualize: bool,
+    pub fps: u32,
+    pub step: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            visualize: false,
+            fps: 10,
+            step: false,
+        }
+    }
+}
+
+pub fn configure(settings: Settings) {
+    _ = SETTINGS.set(settings);
+}
+
+/// A renderer on stdout if `--visualize` was passed, otherwise `None` so solutions can skip
+/// building frames entirely.
+pub fn renderer() -> Option<Renderer<Stdout>> {
+    let settings = SETTINGS.get().copied().unwrap_or_default();
+    settings
+        .visualize
+        .then(|| Renderer::new(stdout(), stdout().is_terminal(), settings))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+}
+
+impl Color {
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+            Color::White => 97,
+            Color::Gray => 90,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cell {
+    pub glyph: char,
+    pub color: Option<Color>,
+}
+
+/// One picture of a simulation: a base grid of glyphs with coloured overlays painted on top.
+/// Points outside the frame are ignored when painting.
+#[derive(Debug, Clone)]
+pub struct Frame {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<Cell>,
+}
+
+impl Frame {
+    pub fn from_fn<F>(width: usize, height: usize, mut glyph: F) -> Self
+    where
+        F: FnMut(Point) -> char,
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| Point::new(x as i32, y as i32)))
+            .map(|pt| Cell {
+                glyph: glyph(pt),
+                color: None,
+            })
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn from_grid<T, F>(grid: &Grid<T>, mut glyph: F) -> Self
+    where
+        F: FnMut(&T) -> char,
+    {
+        Self::from_fn(grid.width(), grid.height(), |pt| {
+            glyph(grid.get((pt.x as usize, pt.y as usize)).unwrap())
+        })
+    }
+
+    pub fn cells(&self) -> &[Cell] {
+        &self.cells
+    }
+
+    fn cell_mut(&mut self, pt: Point) -> Option<&mut Cell> {
+        (pt.x >= 0 && pt.y >= 0 && (pt.x as usize) < self.width && (pt.y as usize) < self.height)
+            .then(|| &mut self.cells[pt.y as usize * self.width + pt.x as usize])
+    }
+
+    /// Recolour `points`, replacing their glyph too if one is given
+    pub fn paint<'a, I>(&mut self, points: I, glyph: Option<char>, color: Color) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        for &pt in points {
+            if let Some(cell) = self.cell_mut(pt) {
+                cell.glyph = glyph.unwrap_or(cell.glyph);
+                cell.color = Some(color);
+            }
+        }
+        self
+    }
+
+    pub fn path<'a, I>(&mut self, points: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        self.paint(points, Some('O'), Color::Yellow)
+    }
+
+    pub fn agents<'a, I>(&mut self, points: I, glyph: char) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        self.paint(points, Some(glyph), Color::Red)
+    }
+
+    pub fn highlight<'a, I>(&mut self, points: I) -> &mut Self
+    where
+        I: IntoIterator<Item = &'a Point>,
+    {
+        self.paint(points, None, Color::Cyan)
+    }
+
+    /// Text for the frame, with ANSI colours only if `color` is set
+    pub fn to_string_colored(&self, color: bool) -> String {
+        let mut out = String::with_capacity(self.cells.len() * 2);
+        for row in self.cells.chunks(self.width.max(1)) {
+            for cell in row {
+                match cell.color {
+                    Some(c) if color => {
+                        out.push_str(&format!("\x1B[{}m{}\x1B[0m", c.ansi_code(), cell.glyph))
+                    }
+                    _ => out.push(cell.glyph),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl std::fmt::Display for Frame {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.to_string_colored(false))
+    }
+}
+
+/// Draws frames in place on a terminal at a fixed rate, optionally waiting for Enter between
+/// frames. When the output isn't a terminal, frames are written one after another as plain text
+/// with no escape codes or delays, so the output can be piped to a file. If whatever reads the
+/// pipe goes away (e.g. `head`), the renderer quietly stops drawing.
+pub struct Renderer<W: Write> {
+    out: W,
+    tty: bool,
+    delay: Duration,
+    step: bool,
+    frames: usize,
+    closed: bool,
+}
+
+impl<W: Write> Renderer<W> {
+    pub fn new(out: W, tty: bool, settings: Settings) -> Self {
+        Self {
+            out,
+            tty,
+            delay: Duration::from_millis(1000 / settings.fps.max(1) as u64),
+            step: settings.step,
+            frames: 0,
+            closed: false,
+        }
+    }
+
+    /// Write one frame. A broken pipe closes the renderer instead of failing, and every later
+    /// draw does nothing.
+    pub fn draw(&mut self, frame: &Frame, caption: &str) -> io::Result<()> {
+        if self.closed {
+            return Ok(());
+        }
+        self.frames += 1;
+        let result = if self.tty {
+            write!(
+                self.out,
+                "\x1B[2J\x1B[1;1H{}\n{}",
+                caption,
+                frame.to_string_colored(true)
+            )
+        } else {
+            writeln!(
+                self.out,
+                "frame {}: {}\n{}",
+                self.frames,
+                caption,
+                frame.to_string_colored(false)
+            )
+        };
+        match result.and_then(|_| self.out.flush()) {
+            Err(e) if e.kind() == ErrorKind::BrokenPipe => {
+                self.closed = true;
+                return Ok(());
+            }
+            result => result?,
+        }
+        if self.step {
+            self.pause();
+        } else if self.tty {
+            std::thread::sleep(self.delay);
+        }
+        Ok(())
+    }
+
+    /// True once the output has gone away, so callers can stop building frames
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    /// Block until Enter is pressed. Does nothing off a terminal.
+    pub fn pause(&mut self) {
+        if self.tty {
+            _ = write!(self.out, "[enter] to continue").and_then(|_| self.out.flush());
+            _ = stdin().read_line(&mut String::new());
+        }
+    }
+
+    pub fn frames_drawn(&self) -> usize {
+        self.frames
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_non_tty_output() {
+        let mut frame = Frame::from_fn(3, 2, |pt| if pt.x == 1 { '#' } else { '.' });
+        frame
+            .path(&[Point::new(0, 0), Point::new(0, 1)])
+            .agents(&[Point::new(2, 1), Point::new(5, 5)], '@');
+        let mut out = Vec::new();
+        let mut renderer = Renderer::new(&mut out, false, Settings::default());
+        renderer.draw(&frame, "tick").unwrap();
+        assert_eq!(renderer.frames_drawn(), 1);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "frame 1: tick\nO#.\nO#@\n\n"
+        );
+        assert!(frame.to_string_colored(true).contains("\x1B[31m@"));
+    }
+
+    /// Accepts `budget` bytes, then fails every write after with `kind`
+    struct Failing {
+        budget: usize,
+        kind: ErrorKind,
+    }
+
+    impl Write for Failing {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            match self.budget.checked_sub(buf.len()) {
+                Some(left) => {
+                    self.budget = left;
+                    Ok(buf.len())
+                }
+                None => Err(self.kind.into()),
+            }
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_closed_pipe() {
+        let frame = Frame::from_fn(2, 2, |_| '.');
+        // room for the first frame only
+        let out = Failing {
+            budget: 30,
+            kind: ErrorKind::BrokenPipe,
+        };
+        let mut renderer = Renderer::new(out, false, Settings::default());
+        renderer.draw(&frame, "first").unwrap();
+        assert!(renderer.draw(&frame, "second").is_ok());
+        assert!(renderer.is_closed());
+        renderer.draw(&frame, "third").unwrap();
+        assert_eq!(renderer.frames_drawn(), 2);
+
+        let out = Failing {
+            budget: 0,
+            kind: ErrorKind::PermissionDenied,
+        };
+        let mut renderer = Renderer::new(out, false, Settings::default());
+        assert!(renderer.draw(&frame, "denied").is_err());
+        assert!(!renderer.is_closed());
+    }
+}
@@ -0,0 +1,417 @@
+use std::{
+    cmp::Ordering,
+    collections::{BinaryHeap, HashMap, HashSet, VecDeque},
+    hash::Hash,
+    ops::Add,
+};
+
+use super::{grid2d::Direction, point::Point};
+
+/// Result of a full search: the cheapest known cost to every reached state, and for each state
+/// every predecessor that reaches it at that cost. The predecessors form a DAG of all optimal
+/// paths back to the source(s).
+#[derive(Debug, Clone)]
+pub struct ShortestPaths<S, C> {
+    pub dist: HashMap<S, C>,
+    pub preds: HashMap<S, Vec<S>>,
+}
+
+impl<S, C> ShortestPaths<S, C>
+where
+    S: Copy + Eq + Hash,
+    C: Copy + Ord,
+{
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.dist.get(state).copied()
+    }
+
+    pub fn contains(&self, state: &S) -> bool {
+        self.dist.contains_key(state)
+    }
+
+    /// Every state that lies on at least one optimal path ending in any of `ends`. Pass only the
+    /// ends that share the best cost if you want the union of all best paths to a goal.
+    pub fn on_any_path<I>(&self, ends: I) -> HashSet<S>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let mut seen = HashSet::new();
+        let mut stack = ends
+            .into_iter()
+            .filter(|s| self.contains(s))
+            .collect::<Vec<_>>();
+        while let Some(state) = stack.pop() {
+            if seen.insert(state) {
+                if let Some(preds) = self.preds.get(&state) {
+                    stack.extend(preds.iter().copied());
+                }
+            }
+        }
+        seen
+    }
+
+    /// The cheapest cost among `goals`, along with every goal that reaches it at that cost.
+    pub fn best_ends<I>(&self, goals: I) -> Option<(C, Vec<S>)>
+    where
+        I: IntoIterator<Item = S>,
+    {
+        let reached = goals
+            .into_iter()
+            .filter_map(|g| self.cost(&g).map(|c| (c, g)))
+            .collect::<Vec<_>>();
+        let best = reached.iter().map(|(c, _)| *c).min()?;
+        Some((
+            best,
+            reached
+                .into_iter()
+                .filter_map(|(c, g)| (c == best).then_some(g))
+                .collect(),
+        ))
+    }
+
+    /// One optimal path from a source to `end` (both included), following the first recorded
+    /// predecessor at each step. The first predecessor is always the one that settled a state,
+    /// so this terminates even when zero-cost cycles make `preds` cyclic.
+    pub fn path_to(&self, end: S) -> Option<Vec<S>> {
+        self.cost(&end)?;
+        let mut path = vec![end];
+        let mut cur = end;
+        while let Some(&prev) = self.preds.get(&cur).and_then(|p| p.first()) {
+            path.push(prev);
+            cur = prev;
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+/// Composite search state for walkers that care which way they are facing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pose {
+    pub pos: Point,
+    pub facing: Direction,
+}
+
+impl Pose {
+    pub fn new(pos: Point, facing: Direction) -> Self {
+        Self { pos, facing }
+    }
+
+    /// Every pose reachable in one move under `model`: a step forward, or a quarter turn either
+    /// way in place. Pair with [`dijkstra`] or [`dijkstra_to`].
+    pub fn successors<C, M>(self, model: &M) -> impl Iterator<Item = (Pose, C)>
+    where
+        M: CostModel<C>,
+    {
+        let forward = Pose::new(self.pos.step(self.facing), self.facing);
+        let left = Pose::new(self.pos, self.facing.turn_left());
+        let right = Pose::new(self.pos, self.facing.turn_right());
+        [
+            model.forward(self).map(|c| (forward, c)),
+            model.turn(self, left.facing).map(|c| (left, c)),
+            model.turn(self, right.facing).map(|c| (right, c)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+/// Caller-supplied prices for moving a [`Pose`] around. Returning `None` forbids the move.
+pub trait CostModel<C> {
+    /// Cost of stepping from `pose` one tile in the direction it faces
+    fn forward(&self, pose: Pose) -> Option<C>;
+    /// Cost of rotating in place from `pose` to face `to`
+    fn turn(&self, pose: Pose, to: Direction) -> Option<C>;
+}
+
+/// Min-heap entry, ordered only by cost so states don't need to implement `Ord`
+struct Entry<S, C> {
+    cost: C,
+    state: S,
+}
+
+impl<S, C: Ord> PartialEq for Entry<S, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cost == other.cost
+    }
+}
+
+impl<S, C: Ord> Eq for Entry<S, C> {}
+
+impl<S, C: Ord> PartialOrd for Entry<S, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S, C: Ord> Ord for Entry<S, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.cmp(&self.cost)
+    }
+}
+
+/// Unweighted breadth-first search from one or many sources, visiting everything reachable.
+pub fn bfs<S, F, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+) -> ShortestPaths<S, usize>
+where
+    S: Copy + Eq + Hash,
+    F: FnMut(S) -> I,
+    I: IntoIterator<Item = S>,
+{
+    let mut dist = HashMap::new();
+    let mut preds: HashMap<S, Vec<S>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if dist.insert(start, 0).is_none() {
+            queue.push_back(start);
+        }
+    }
+    while let Some(cur) = queue.pop_front() {
+        let next_cost = dist[&cur] + 1;
+        for nbr in successors(cur) {
+            match dist.get(&nbr) {
+                None => {
+                    dist.insert(nbr, next_cost);
+                    preds.insert(nbr, vec![cur]);
+                    queue.push_back(nbr);
+                }
+                Some(&cost) if cost == next_cost => {
+                    preds.entry(nbr).or_default().push(cur);
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    ShortestPaths { dist, preds }
+}
+
+/// Dijkstra from one or many sources, visiting everything reachable. `C::default()` is used as
+/// the zero cost of the sources. Step costs must not be negative. Zero-cost steps are fine, but
+/// a zero-cost cycle records its states as each other's predecessors, so `preds` is only a DAG
+/// without them. Sources never get predecessors.
+pub fn dijkstra<S, C, F, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+) -> ShortestPaths<S, C>
+where
+    S: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut dist = HashMap::new();
+    let mut preds: HashMap<S, Vec<S>> = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    let mut sources = HashSet::new();
+    for start in starts {
+        dist.insert(start, C::default());
+        sources.insert(start);
+        heap.push(Entry {
+            cost: C::default(),
+            state: start,
+        });
+    }
+    while let Some(Entry { cost, state }) = heap.pop() {
+        if dist.get(&state).is_some_and(|&best| cost > best) {
+            continue; // stale entry
+        }
+        for (nbr, step) in successors(state) {
+            let next_cost = cost + step;
+            match dist.get(&nbr).map(|best| next_cost.cmp(best)) {
+                None | Some(Ordering::Less) => {
+                    dist.insert(nbr, next_cost);
+                    preds.insert(nbr, vec![state]);
+                    heap.push(Entry {
+                        cost: next_cost,
+                        state: nbr,
+                    });
+                }
+                Some(Ordering::Equal) if !sources.contains(&nbr) => {
+                    preds.entry(nbr).or_default().push(state)
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    ShortestPaths { dist, preds }
+}
+
+/// Dijkstra that stops at the first goal state popped. Returns its cost and one cheapest path
+/// (sources and goal included).
+pub fn dijkstra_to<S, C, F, I, G>(
+    starts: impl IntoIterator<Item = S>,
+    mut successors: F,
+    mut is_goal: G,
+) -> Option<(C, Vec<S>)>
+where
+    S: Copy + Eq + Hash,
+    C: Copy + Ord + Default + Add<Output = C>,
+    F: FnMut(S) -> I,
+    I: IntoIterator<Item = (S, C)>,
+    G: FnMut(&S) -> bool,
+{
+    let mut dist = HashMap::new();
+    let mut came_from = HashMap::new();
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        dist.insert(start, C::default());
+        heap.push(Entry {
+            cost: C::default(),
+            state: start,
+        });
+    }
+    while let Some(Entry { cost, state }) = heap.pop() {
+        if dist.get(&state).is_some_and(|&best| cost > best) {
+            continue;
+        }
+        if is_goal(&state) {
+            let mut path = vec![state];
+            let mut cur = state;
+            while let Some(&prev) = came_from.get(&cur) {
+                path.push(prev);
+                cur = prev;
+            }
+            path.reverse();
+            return Some((cost, path));
+        }
+        for (nbr, step) in successors(state) {
+            let next_cost = cost + step;
+            if dist.get(&nbr).is_none_or(|&best| next_cost < best) {
+                dist.insert(nbr, next_cost);
+                came_from.insert(nbr, state);
+                heap.push(Entry {
+                    cost: next_cost,
+                    state: nbr,
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const TEST_INPUT: &str = "\
+.....
+.###.
+...#.
+.#...";
+
+    fn open_nbrs(grid: &[Vec<char>]) -> impl Fn(Point) -> Vec<Point> + '_ {
+        move |pt: Point| {
+            pt.cardinal_neighbors()
+                .into_iter()
+                .filter(|n| {
+                    n.x >= 0
+                        && n.y >= 0
+                        && grid
+                            .get(n.y as usize)
+                            .and_then(|row| row.get(n.x as usize))
+                            .is_some_and(|&c| c == '.')
+                })
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_bfs_distance_map() {
+        let grid = TEST_INPUT
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        let paths = bfs([Point::new(0, 0)], open_nbrs(&grid));
+        assert_eq!(paths.cost(&Point::new(4, 3)), Some(7));
+        assert_eq!(paths.cost(&Point::new(1, 1)), None);
+        // one route along the top, one through the bottom, both 7 steps
+        assert_eq!(paths.on_any_path([Point::new(4, 3)]).len(), 14);
+    }
+
+    #[test]
+    fn test_dijkstra_matches_bfs() {
+        let grid = TEST_INPUT
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        let nbrs = open_nbrs(&grid);
+        let weighted = dijkstra([Point::new(0, 0)], |p| {
+            nbrs(p).into_iter().map(|n| (n, 1u32))
+        });
+        let unweighted = bfs([Point::new(0, 0)], &nbrs);
+        for (pt, cost) in unweighted.dist {
+            assert_eq!(weighted.cost(&pt), Some(cost as u32));
+        }
+        let (cost, path) = dijkstra_to(
+            [Point::new(0, 0)],
+            |p| nbrs(p).into_iter().map(|n| (n, 1u32)),
+            |p| *p == Point::new(4, 3),
+        )
+        .unwrap();
+        assert_eq!(cost, 7);
+        assert_eq!(path.len(), 8);
+    }
+
+    #[test]
+    fn test_zero_cost_cycle() {
+        // 0 <-> 1 <-> 2 for free, 2 -> 3 costs 1
+        let edges = |s: u8| match s {
+            0 => vec![(1, 0)],
+            1 => vec![(0, 0), (2, 0)],
+            2 => vec![(1, 0), (3, 1u32)],
+            _ => vec![],
+        };
+        let paths = dijkstra([0u8], edges);
+        assert!(!paths.preds.contains_key(&0));
+        assert_eq!(paths.path_to(3), Some(vec![0, 1, 2, 3]));
+        assert_eq!(paths.on_any_path([3]).len(), 4);
+    }
+
+    struct Reindeer<'a> {
+        grid: &'a [Vec<char>],
+    }
+
+    impl CostModel<u32> for Reindeer<'_> {
+        fn forward(&self, pose: Pose) -> Option<u32> {
+            let next = pose.pos.step(pose.facing);
+            (next.x >= 0 && next.y >= 0)
+                .then(|| self.grid.get(next.y as usize)?.get(next.x as usize))
+                .flatten()
+                .is_some_and(|&c| c == '.')
+                .then_some(1)
+        }
+
+        fn turn(&self, _: Pose, _: Direction) -> Option<u32> {
+            Some(1000)
+        }
+    }
+
+    #[test]
+    fn test_pose_search() {
+        let grid = TEST_INPUT
+            .lines()
+            .map(|l| l.chars().collect())
+            .collect::<Vec<Vec<char>>>();
+        let model = Reindeer { grid: &grid };
+        let paths = dijkstra([Pose::new(Point::new(0, 0), Direction::Right)], |p| {
+            p.successors(&model)
+        });
+        let goal = Point::new(4, 3);
+        let (cost, ends) = paths
+            .best_ends(Direction::ALL.map(|d| Pose::new(goal, d)))
+            .unwrap();
+        // the top route needs only one turn, the bottom one needs four
+        assert_eq!(cost, 1007);
+        assert_eq!(ends, vec![Pose::new(goal, Direction::Down)]);
+        let path = paths.path_to(ends[0]).unwrap();
+        assert_eq!(path.first().unwrap().pos, Point::new(0, 0));
+        let tiles = paths
+            .on_any_path(ends)
+            .into_iter()
+            .map(|p| p.pos)
+            .collect::<HashSet<_>>();
+        assert_eq!(tiles.len(), 8);
+    }
+}
@@ -0,0 +1,204 @@
+use std::collections::{hash_map, HashMap};
+
+use simple_grid::Grid;
+
+use super::point::Point;
+
+/// Unbounded grid that only stores occupied cells. Bounds are whatever the occupied cells span,
+/// so coordinates can go negative.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    /// Drawn for unoccupied cells by `Display`
+    pub empty: char,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: HashMap::new(),
+            empty: '.',
+        }
+    }
+
+    pub fn with_empty(mut self, empty: char) -> Self {
+        self.empty = empty;
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn insert(&mut self, pt: Point, value: T) -> Option<T> {
+        self.cells.insert(pt, value)
+    }
+
+    pub fn remove(&mut self, pt: &Point) -> Option<T> {
+        self.cells.remove(pt)
+    }
+
+    pub fn get(&self, pt: &Point) -> Option<&T> {
+        self.cells.get(pt)
+    }
+
+    pub fn get_mut(&mut self, pt: &Point) -> Option<&mut T> {
+        self.cells.get_mut(pt)
+    }
+
+    pub fn contains(&self, pt: &Point) -> bool {
+        self.cells.contains_key(pt)
+    }
+
+    pub fn entry(&mut self, pt: Point) -> hash_map::Entry<'_, Point, T> {
+        self.cells.entry(pt)
+    }
+
+    pub fn iter(&self) -> hash_map::Iter<'_, Point, T> {
+        self.cells.iter()
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = &Point> {
+        self.cells.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.cells.values()
+    }
+
+    /// Inclusive `(min, max)` corners of the occupied cells, `None` when empty
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        let mut pts = self.cells.keys();
+        let first = *pts.next()?;
+        Some(pts.fold((first, first), |(min, max), pt| {
+            (
+                Point::new(min.x.min(pt.x), min.y.min(pt.y)),
+                Point::new(max.x.max(pt.x), max.y.max(pt.y)),
+            )
+        }))
+    }
+
+    /// Occupied up/right/down/left neighbors of `pt`
+    pub fn cardinal_neighbors(&self, pt: Point) -> impl Iterator<Item = (Point, &T)> {
+        pt.cardinal_neighbors()
+            .into_iter()
+            .filter_map(|n| self.get(&n).map(|v| (n, v)))
+    }
+
+    /// Occupied neighbors of `pt` in all eight directions
+    pub fn neighbors(&self, pt: Point) -> impl Iterator<Item = (Point, &T)> {
+        pt.cardinal_neighbors()
+            .into_iter()
+            .chain(pt.diag_neighbors())
+            .filter_map(|n| self.get(&n).map(|v| (n, v)))
+    }
+
+    /// Keep the cells of a dense grid for which `keep` is true, with the grid's `(0, 0)` placed at
+    /// `origin`. Passing the origin [`SparseGrid::to_dense`] returned gives back the same points.
+    pub fn from_dense<F>(grid: &Grid<T>, origin: Point, mut keep: F) -> Self
+    where
+        T: Clone,
+        F: FnMut(&T) -> bool,
+    {
+        grid.cells_with_indices_iter()
+            .filter(|(_, v)| keep(v))
+            .map(|(idx, v)| (origin + Point::from(idx), v.clone()))
+            .collect()
+    }
+
+    /// Dense copy of the bounding box, with unoccupied cells set to `fill`. Also returns the
+    /// point that ends up at `(0, 0)` so positions can be mapped back.
+    pub fn to_dense(&self, fill: T) -> Option<(Grid<T>, Point)>
+    where
+        T: Clone,
+    {
+        let (min, max) = self.bounds()?;
+        let (width, height) = ((max.x - min.x + 1) as usize, (max.y - min.y + 1) as usize);
+        let mut grid = Grid::new(width, height, vec![fill; width * height]);
+        for (pt, v) in &self.cells {
+            let offset = *pt - min;
+            grid.replace_cell((offset.x as usize, offset.y as usize), v.clone());
+        }
+        Some((grid, min))
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        Self {
+            cells: iter.into_iter().collect(),
+            ..Self::new()
+        }
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        self.cells.extend(iter);
+    }
+}
+
+impl<T> IntoIterator for SparseGrid<T> {
+    type Item = (Point, T);
+    type IntoIter = hash_map::IntoIter<Point, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.cells.into_iter()
+    }
+}
+
+impl<T> std::fmt::Display for SparseGrid<T>
+where
+    T: std::fmt::Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds() else {
+            return Ok(());
+        };
+        for y in min.y..=max.y {
+            for x in min.x..=max.x {
+                match self.get(&Point::new(x, y)) {
+                    Some(v) => write!(f, "{v}")?,
+                    None => write!(f, "{}", self.empty)?,
+                }
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_bounds_and_display() {
+        let mut grid: SparseGrid<char> = [((-1, 2).into(), 'a'), ((1, 0).into(), 'b')]
+            .into_iter()
+            .collect();
+        assert_eq!(grid.bounds(), Some(((-1, 0).into(), (1, 2).into())));
+        assert_eq!(grid.to_string(), "..b\n...\na..\n");
+        grid.insert((0, 1).into(), 'c');
+        assert_eq!(grid.neighbors((0, 0).into()).count(), 2);
+        assert_eq!(grid.cardinal_neighbors((0, 0).into()).count(), 2);
+        assert_eq!(grid.cardinal_neighbors((0, 2).into()).count(), 2);
+
+        let (dense, origin) = grid.to_dense(' ').unwrap();
+        assert_eq!(origin, (-1, 0).into());
+        assert_eq!(dense.get((2, 0)), Some(&'b'));
+        let back = SparseGrid::from_dense(&dense, origin, |&c| c != ' ');
+        assert_eq!(back, grid);
+        assert_eq!(back.get(&(-1, 2).into()), Some(&'a'));
+    }
+}
@@ -1,16 +1,102 @@
 use std::fs::read_to_string;
 use std::io::Write;
+use std::ops::Deref;
+use std::path::Path;
 
-use std::sync::Once;
+use std::sync::{Once, OnceLock};
+
+use simple_grid::Grid;
+
+use super::parse::{sections, Section};
 
 static INIT_TEST_LOGGER: Once = Once::new();
+static DEBUG: OnceLock<bool> = OnceLock::new();
+
+/// Turn on the extra output some solutions print with `--debug`, set once by `main`
+pub fn set_debug(enabled: bool) {
+    _ = DEBUG.set(enabled);
+}
+
+pub fn debug() -> bool {
+    DEBUG.get().copied().unwrap_or(false)
+}
+
+/// Puzzle input with line endings normalized: `\r\n` becomes `\n`, trailing whitespace is
+/// stripped from every line and trailing blank lines are dropped. Derefs to the cleaned up
+/// `str`, so it can go straight into any `&str` parser.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Input {
+    text: String,
+}
+
+impl Input {
+    pub fn new(raw: &str) -> Self {
+        let mut text = raw
+            .lines()
+            .map(str::trim_end)
+            .collect::<Vec<_>>()
+            .join("\n");
+        text.truncate(text.trim_end().len());
+        Self { text }
+    }
+
+    /// Read and normalize a file, panicking with its path if it can't be read
+    pub fn read<P: AsRef<Path>>(path: P) -> Self {
+        let path = path.as_ref();
+        let raw = read_to_string(path)
+            .unwrap_or_else(|e| panic!("error reading {}: {e}", path.display()));
+        Self::new(&raw)
+    }
+
+    pub fn lines(&self) -> std::str::Lines<'_> {
+        self.text.lines()
+    }
 
-pub fn read_lines(filename: &str) -> Vec<String> {
-    read_to_string(filename)
-        .expect("error reading line")
-        .lines()
-        .map(|l| l.to_owned())
-        .collect()
+    pub fn sections(&self) -> Vec<Section<'_>> {
+        sections(&self.text)
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        self.text.as_bytes()
+    }
+
+    /// Every line as a row of chars. Panics on ragged rows.
+    pub fn grid(&self) -> Grid<char> {
+        self.grid_with(|c| c)
+    }
+
+    /// Every line as a row of cells, converting each char with `f`. Panics on ragged rows.
+    pub fn grid_with<T, F: FnMut(char) -> T>(&self, mut f: F) -> Grid<T> {
+        let width = self.lines().next().map_or(0, |l| l.chars().count());
+        let mut height = 0;
+        let mut cells = vec![];
+        for (row, line) in self.lines().enumerate() {
+            let len = line.chars().count();
+            assert_eq!(
+                len,
+                width,
+                "line {} is {len} wide, expected {width}",
+                row + 1
+            );
+            cells.extend(line.chars().map(&mut f));
+            height += 1;
+        }
+        Grid::new(width, height, cells)
+    }
+}
+
+impl From<&str> for Input {
+    fn from(raw: &str) -> Self {
+        Self::new(raw)
+    }
+}
+
+impl Deref for Input {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.text
+    }
 }
 
 pub fn wait_millis(s: u64) {
@@ -30,3 +116,18 @@ pub fn test_setup() {
             .init()
     });
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_input_normalizes() {
+        let input = Input::from("ab  \r\ncd\r\n\r\nef\r\n\n\n");
+        assert_eq!(&*input, "ab\ncd\n\nef");
+        assert_eq!(input.sections().len(), 2);
+        let grid = Input::from("ab\r\ncd").grid();
+        assert_eq!((grid.width(), grid.height()), (2, 2));
+        assert_eq!(Input::from("12\n").bytes(), b"12");
+    }
+}